@@ -1,9 +1,12 @@
 use crate::{
+    class::{Class, ClassId},
     context::Selection,
-    field::allocate_padding,
-    gui::{ClassListPanel, InspectorPanel, ToolBarPanel, ToolBarResponse},
+    field::{allocate_padding, Field},
+    gui::{ClassListPanel, InspectorPanel, ToolBarPanel, ToolBarResponse, WatchListPanel},
+    history::Command,
     process::YProcess,
-    state::StateRef,
+    project::FieldClipboard,
+    state::{GlobalState, StateRef},
 };
 use eframe::{egui::Context, epaint::Color32, App, Frame};
 use std::{sync::Once, time::Duration};
@@ -12,6 +15,7 @@ pub struct YClassApp {
     class_list: ClassListPanel,
     inspector: InspectorPanel,
     tool_bar: ToolBarPanel,
+    watch_list: WatchListPanel,
     state: StateRef,
 }
 
@@ -21,11 +25,232 @@ impl YClassApp {
             class_list: ClassListPanel::new(state),
             inspector: InspectorPanel::new(state),
             tool_bar: ToolBarPanel::new(state),
+            watch_list: WatchListPanel::new(state),
             state,
         }
     }
 }
 
+impl YClassApp {
+    fn undo_redo(&mut self, redo: bool) {
+        let state = &mut *self.state.borrow_mut();
+        let process = state.process.read();
+
+        let (done, available) = if redo {
+            let available = state.history.can_redo();
+            let done = state.history.redo(&mut state.class_list, process.as_ref());
+            (done, available)
+        } else {
+            let available = state.history.can_undo();
+            let done = state.history.undo(&mut state.class_list, process.as_ref());
+            (done, available)
+        };
+        drop(process);
+
+        match (done, available, redo) {
+            (false, false, false) => _ = state.toasts.info("Nothing to undo"),
+            (false, false, true) => _ = state.toasts.info("Nothing to redo"),
+            (false, true, _) if state.is_read_only() => {
+                _ = state
+                    .toasts
+                    .warning("Memory writes can't be reverted in read-only mode")
+            }
+            (false, true, false) => _ = state.toasts.error("Failed to undo the edit"),
+            (false, true, true) => _ = state.toasts.error("Failed to redo the edit"),
+            (true, ..) => state.dummy = false,
+        }
+
+        // Selected field might have been removed.
+        if let Some(sel) = &state.selection {
+            if !state
+                .class_list
+                .by_id(sel.container_id)
+                .is_some_and(|c| c.fields.iter().any(|f| f.id() == sel.field_id))
+            {
+                state.selection = None;
+            }
+        }
+    }
+}
+
+impl YClassApp {
+    /// Copies the selected fields and everything between them.
+    fn copy_fields(&mut self, ctx: &Context, cut: bool) {
+        let state = &mut *self.state.borrow_mut();
+
+        let Some(selection) = state.selection.as_ref() else {
+            state.toasts.info("Select fields to copy");
+            return;
+        };
+        let class_id = selection.container_id;
+        let Some(class) = state.class_list.by_id(class_id) else {
+            return;
+        };
+
+        let positions = selection.positions(class);
+        let (Some(&first), Some(&last)) = (positions.first(), positions.last()) else {
+            return;
+        };
+
+        let clipboard =
+            FieldClipboard::store(&class.fields[first..=last], state.class_list.classes());
+        ctx.copy_text(clipboard.to_string());
+
+        if cut {
+            // Cut fields are replaced with padding to keep offsets of the following fields.
+            let command = Command::Splice {
+                class_id,
+                at: first,
+                len: last - first + 1,
+                fields: allocate_padding(clipboard.size()),
+            };
+            state.selection = None;
+            state.history.apply(command, &mut state.class_list, None);
+            state.dummy = false;
+        }
+
+        state.copied_fields = Some(clipboard);
+    }
+
+    /// Pastes fields over the selected field or at the end of the selected class.
+    fn paste_fields(&mut self, text: Option<String>) {
+        let state = &mut *self.state.borrow_mut();
+
+        let clipboard = match text {
+            Some(text) => match FieldClipboard::from_str(&text) {
+                Some(clipboard) if !clipboard.fits() => {
+                    state.toasts.error("Copied fields are too large to paste");
+                    return;
+                }
+                Some(clipboard) => clipboard,
+                None => {
+                    state.toasts.error("Clipboard doesn't contain fields");
+                    return;
+                }
+            },
+            None => match state.copied_fields.clone() {
+                Some(clipboard) => clipboard,
+                None => {
+                    state.toasts.info("Nothing to paste");
+                    return;
+                }
+            },
+        };
+
+        let target = match state.selection.as_ref() {
+            Some(selection) => state
+                .class_list
+                .by_id(selection.container_id)
+                .and_then(|c| {
+                    let at = *selection.positions(c).first()?;
+                    Some((c.id(), at))
+                }),
+            None => state
+                .class_list
+                .selected_class()
+                .map(|c| (c.id(), c.fields.len())),
+        };
+        let Some((class_id, at)) = target else {
+            state.toasts.info("Select a class or a field to paste into");
+            return;
+        };
+
+        let existing = class_ids(state.class_list.classes());
+        let size = clipboard.size();
+        let mut fields = clipboard.load(&mut state.class_list);
+
+        // Classes that pointers point to might have been created.
+        let created = class_ids(state.class_list.classes())
+            .into_iter()
+            .filter(|id| !existing.contains(id));
+
+        let class = state.class_list.by_id(class_id).unwrap();
+        let (len, replaced) = fields_to_replace(class, at, size);
+        fields.extend(allocate_padding(replaced.saturating_sub(size)));
+
+        let command = Command::Splice {
+            class_id,
+            at,
+            len,
+            fields,
+        };
+        if let Some(inverse) = command.apply(&mut state.class_list, None) {
+            let mut undo = vec![inverse];
+            undo.extend(created.map(Command::RemoveClass));
+            state.history.push(Command::Batch(undo));
+        }
+
+        state.selection = None;
+        state.dummy = false;
+    }
+}
+
+/// Splices every selected field, starting from the first one, and records all splices
+/// as a single edit. `splice` returns the number of fields replaced at the position and
+/// the new fields, `None` skips the field. Returns the number of skipped fields.
+fn splice_selection(
+    state: &mut GlobalState,
+    selection: &Selection,
+    mut splice: impl FnMut(&Class, usize) -> Option<(usize, Vec<Box<dyn Field>>)>,
+) -> usize {
+    let class_id = selection.container_id;
+    let Some(class) = state.class_list.by_id(class_id) else {
+        return 0;
+    };
+    let ids = selection
+        .positions(class)
+        .into_iter()
+        .map(|i| class.fields[i].id())
+        .collect::<Vec<_>>();
+
+    let (mut inverse, mut skipped) = (vec![], 0);
+    for id in ids {
+        let class = state.class_list.by_id(class_id).unwrap();
+        // Field might have been replaced while splicing the previous one.
+        let Some(at) = class.fields.iter().position(|f| f.id() == id) else {
+            continue;
+        };
+        let Some((len, fields)) = splice(class, at) else {
+            skipped += 1;
+            continue;
+        };
+
+        let command = Command::Splice {
+            class_id,
+            at,
+            len,
+            fields,
+        };
+        inverse.extend(command.apply(&mut state.class_list, None));
+    }
+
+    if !inverse.is_empty() {
+        inverse.reverse();
+        state.history.push(Command::Batch(inverse));
+    }
+    skipped
+}
+
+fn class_ids(classes: &[Class]) -> Vec<ClassId> {
+    classes.iter().map(|c| c.id()).collect()
+}
+
+/// Returns the number of fields starting at `pos` that together take at least `size` bytes,
+/// and their total size. The size is smaller than requested if the class ends earlier.
+fn fields_to_replace(class: &Class, pos: usize, size: usize) -> (usize, usize) {
+    let (mut len, mut replaced) = (0, 0);
+    for field in class.fields.iter().skip(pos) {
+        if replaced >= size {
+            break;
+        }
+
+        replaced += field.size();
+        len += 1;
+    }
+
+    (len, replaced)
+}
+
 impl App for YClassApp {
     fn update(&mut self, ctx: &Context, frame: &mut Frame) {
         ctx.request_repaint_after(Duration::from_millis(100));
@@ -42,11 +267,18 @@ impl App for YClassApp {
 
                 if let Some(cid) = state
                     .selection
+                    .as_ref()
                     .map(|s| s.container_id)
                     .or_else(|| state.class_list.selected())
                 {
-                    let class = state.class_list.by_id_mut(cid).unwrap();
-                    class.fields.extend(allocate_padding(n));
+                    let class = state.class_list.by_id(cid).unwrap();
+                    let command = Command::Splice {
+                        class_id: cid,
+                        at: class.fields.len(),
+                        len: 0,
+                        fields: allocate_padding(n),
+                    };
+                    state.history.apply(command, &mut state.class_list, None);
 
                     state.dummy = false;
                 }
@@ -54,120 +286,94 @@ impl App for YClassApp {
             Some(ToolBarResponse::Remove(n)) => {
                 let state = &mut *self.state.borrow_mut();
 
-                if let Some(Selection {
-                    container_id,
-                    field_id,
-                    ..
-                }) = state.selection
-                {
-                    let class = state.class_list.by_id_mut(container_id).unwrap();
-                    let mut discrd_sel = false;
-                    let pos = class
-                        .fields
-                        .iter()
-                        .position(|f| {
-                            discrd_sel |= state
-                                .selection
-                                .map(|s| s.field_id == f.id())
-                                .unwrap_or(false);
-
-                            f.id() == field_id
-                        })
-                        .unwrap();
-                    if discrd_sel {
-                        state.selection = None;
-                    }
+                if let Some(selection) = state.selection.take() {
+                    if selection.others.is_empty() {
+                        // Selected field is always the first one to be removed.
+                        let class = state.class_list.by_id(selection.container_id).unwrap();
+                        let pos = class
+                            .fields
+                            .iter()
+                            .position(|f| f.id() == selection.field_id)
+                            .unwrap();
 
-                    let from = pos.min(class.fields.len());
-                    let to = (pos + n).min(class.fields.len());
+                        let from = pos.min(class.fields.len());
+                        let to = (pos + n).min(class.fields.len());
+
+                        let command = Command::Splice {
+                            class_id: selection.container_id,
+                            at: from,
+                            len: to - from,
+                            fields: vec![],
+                        };
+                        state.history.apply(command, &mut state.class_list, None);
+                    } else {
+                        splice_selection(state, &selection, |_, _| Some((1, vec![])));
+                    }
 
-                    class.fields.drain(from..to);
                     state.dummy = false;
                 }
             }
             Some(ToolBarResponse::Insert(n)) => {
                 let state = &mut *self.state.borrow_mut();
 
-                if let Some(Selection {
-                    container_id,
-                    field_id,
-                    ..
-                }) = state.selection
-                {
-                    let class = state.class_list.by_id_mut(container_id).unwrap();
-                    let pos = class
-                        .fields
-                        .iter()
-                        .position(|f| f.id() == field_id)
-                        .unwrap();
-                    let mut padding = allocate_padding(n);
-
-                    while let Some(field) = padding.pop() {
-                        class.fields.insert(pos, field);
-                    }
-
+                if let Some(selection) = state.selection.clone() {
+                    splice_selection(state, &selection, |_, _| Some((0, allocate_padding(n))));
                     state.dummy = false;
                 }
             }
             Some(ToolBarResponse::ChangeKind(new)) => {
                 let state = &mut *self.state.borrow_mut();
 
-                if let Some(Selection {
-                    container_id,
-                    field_id,
-                    ..
-                }) = state.selection
-                {
-                    let class = state.class_list.by_id_mut(container_id).unwrap();
-                    let pos = class
-                        .fields
-                        .iter()
-                        .position(|f| f.id() == field_id)
-                        .unwrap();
-
-                    let (old_size, old_name) = (class.fields[pos].size(), class.fields[pos].name());
-                    if old_size > new.size() {
-                        let mut padding = allocate_padding(old_size - new.size());
-                        class.fields[pos] = new.into_field(old_name);
-                        while let Some(pad) = padding.pop() {
-                            class.fields.insert(pos + 1, pad);
+                if let Some(selection) = state.selection.take() {
+                    let mut changed = vec![];
+                    let skipped = splice_selection(state, &selection, |class, pos| {
+                        let (steal_len, steal_size) = fields_to_replace(class, pos, new.size());
+                        if steal_size < new.size() {
+                            return None;
                         }
 
-                        state.selection.as_mut().unwrap().field_id = class.fields[pos].id();
-                    } else {
-                        let (mut steal_size, mut steal_len) = (0, 0);
-                        while steal_size < new.size() {
-                            if pos >= class.fields.len() {
-                                break;
-                            }
+                        let field = new.into_field(class.fields[pos].name());
+                        changed.push((pos, field.id()));
 
-                            let index = pos + steal_len;
-                            if index >= class.fields.len() {
-                                break;
-                            }
+                        let mut fields = vec![field];
+                        fields.extend(allocate_padding(steal_size - new.size()));
+                        Some((steal_len, fields))
+                    });
 
-                            steal_size += class.fields[index].size();
-                            steal_len += 1;
+                    match skipped {
+                        0 => {}
+                        1 => _ = state.toasts.error("Not enough space for a new field"),
+                        n => {
+                            _ = state
+                                .toasts
+                                .error(format!("Not enough space for {n} new fields"))
                         }
+                    }
 
-                        if steal_size < new.size() {
-                            state.toasts.error("Not enough space for a new field");
-                        } else {
-                            class.fields.drain(pos..pos + steal_len);
-                            let mut padding = allocate_padding(steal_size - new.size());
-                            class.fields.insert(pos, new.into_field(old_name));
-
-                            while let Some(pad) = padding.pop() {
-                                class.fields.insert(pos + 1, pad);
-                            }
-
-                            state.selection.as_mut().unwrap().field_id = class.fields[pos].id();
+                    // New fields are selected instead of the replaced ones.
+                    state.selection = match changed.first() {
+                        Some(&(pos, field_id)) => {
+                            let class = state.class_list.by_id(selection.container_id).unwrap();
+                            Some(Selection {
+                                address: selection.class_address
+                                    + state.class_list.base_size(class.id())
+                                    + class.offset_of(pos),
+                                field_id,
+                                others: changed[1..].iter().map(|(_, id)| *id).collect(),
+                                ..selection
+                            })
                         }
-                    }
+                        None => Some(selection),
+                    };
 
                     state.dummy = false;
                 }
             }
+            Some(ToolBarResponse::Undo) => self.undo_redo(false),
+            Some(ToolBarResponse::Redo) => self.undo_redo(true),
+            Some(ToolBarResponse::CopyFields) => self.copy_fields(ctx, false),
+            Some(ToolBarResponse::CutFields) => self.copy_fields(ctx, true),
+            Some(ToolBarResponse::PasteFields(text)) => self.paste_fields(text),
             Some(ToolBarResponse::MemflowAttach(os)) => {
                 let state = self.state.borrow_mut();
                 *state.os.write() = Some(os);
@@ -181,7 +387,8 @@ impl App for YClassApp {
                     .try_write()
                 {
                     *process = None;
-                    
+                    state.freezes.clear();
+
                     // frame.set_window_title("YClass");
                 } else {
                     state.toasts.warning("Process is currently in use");
@@ -197,11 +404,12 @@ impl App for YClassApp {
                         .clone() /* ??? */
                         .try_write()
                     {
-                        match YProcess::attach(os.clone(), pid) {
+                        match YProcess::attach(os.clone(), pid, state.read_only.clone()) {
                             Ok(proc) => {
                                 // frame.set_window_title(&format!("YClass - Attached to {pid}"));
                                 match proc.name() {
                                     Ok(name) => {
+                                        state.target.process = Some(name.clone());
                                         state.config.last_attached_process_name = Some(name);
                                         state.config.save();
                                     }
@@ -212,6 +420,8 @@ impl App for YClassApp {
                                     }
                                 }
 
+                                // Freezes belong to the previous process.
+                                state.freezes.clear();
                                 *process = Some(proc);
                             }
                             Err(e) => {
@@ -227,10 +437,12 @@ impl App for YClassApp {
                     state.toasts.warning("Process is currently in use");
                 }
             }
+            Some(ToolBarResponse::ToggleWatchList) => self.watch_list.toggle(),
             None => {}
         }
 
         self.class_list.show(ctx);
+        self.watch_list.show(ctx);
         self.inspector.show(ctx);
         crate::gui::show_logger(ctx);
 
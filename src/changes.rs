@@ -0,0 +1,177 @@
+//! This module tracks changes of the memory drawn in the inspector.
+//! Every observed value keeps the time its bytes changed and a short history of its values.
+use crate::field::FieldKind;
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+pub const DEFAULT_FADE_MS: u64 = 1500;
+/// Maximum amount of values kept in the history.
+const HISTORY_LEN: usize = 600;
+/// Minimum time between two values of the history.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+/// Values that weren't observed for this long are forgotten.
+const EXPIRE_AFTER: Duration = Duration::from_secs(10);
+
+struct Entry {
+    bytes: Vec<u8>,
+    /// When every byte changed last time.
+    changed: Vec<Option<Instant>>,
+    history: VecDeque<(Instant, Vec<u8>)>,
+    seen: Instant,
+}
+
+/// Field whose history is shown in the plot window.
+#[derive(Debug, Clone)]
+pub struct Plot {
+    pub address: usize,
+    pub kind: FieldKind,
+    /// Name of the field, e.g. `Player.health`.
+    pub label: String,
+}
+
+pub struct ChangeTracker {
+    /// Entries are keyed by address and size.
+    entries: RefCell<HashMap<(usize, usize), Entry>>,
+    plots: RefCell<Vec<Plot>>,
+    fade: Cell<Duration>,
+}
+
+impl ChangeTracker {
+    pub fn new(fade_ms: u64) -> Self {
+        Self {
+            entries: RefCell::default(),
+            plots: RefCell::default(),
+            fade: Cell::new(Duration::from_millis(fade_ms)),
+        }
+    }
+
+    /// Records bytes read at `address`.
+    /// Returns how much every byte should be highlighted, from `1` when it just changed to `0`.
+    pub fn observe(&self, address: usize, bytes: &[u8]) -> Vec<f32> {
+        let now = Instant::now();
+        let mut entries = self.entries.borrow_mut();
+
+        let entry = entries
+            .entry((address, bytes.len()))
+            .or_insert_with(|| Entry {
+                bytes: bytes.to_vec(),
+                changed: vec![None; bytes.len()],
+                history: VecDeque::new(),
+                seen: now,
+            });
+
+        for (i, (old, new)) in entry.bytes.iter().zip(bytes).enumerate() {
+            if old != new {
+                entry.changed[i] = Some(now);
+            }
+        }
+        entry.bytes.copy_from_slice(bytes);
+        entry.seen = now;
+
+        if entry
+            .history
+            .back()
+            .is_none_or(|(at, _)| now - *at >= SAMPLE_INTERVAL)
+        {
+            if entry.history.len() == HISTORY_LEN {
+                entry.history.pop_front();
+            }
+            entry.history.push_back((now, bytes.to_vec()));
+        }
+
+        let fade = self.fade.get().as_secs_f32();
+        entry
+            .changed
+            .iter()
+            .map(|changed| match changed {
+                Some(at) if fade > 0. => (1. - (now - *at).as_secs_f32() / fade).max(0.),
+                _ => 0.,
+            })
+            .collect()
+    }
+
+    /// Values of the field of `kind` at `address`, paired with seconds passed since they were read.
+    pub fn history(&self, address: usize, kind: FieldKind) -> Vec<(f32, f64)> {
+        let now = Instant::now();
+        let entries = self.entries.borrow();
+        let Some(entry) = entries.get(&(address, kind.size())) else {
+            return vec![];
+        };
+
+        entry
+            .history
+            .iter()
+            .filter_map(|(at, bytes)| Some(((now - *at).as_secs_f32(), decode(kind, bytes)?)))
+            .collect()
+    }
+
+    /// Forgets values that aren't drawn anymore.
+    pub fn prune(&self) {
+        let now = Instant::now();
+        let plots = self.plots.borrow();
+
+        self.entries.borrow_mut().retain(|(address, size), entry| {
+            now - entry.seen < EXPIRE_AFTER
+                || plots
+                    .iter()
+                    .any(|p| p.address == *address && p.kind.size() == *size)
+        });
+    }
+
+    pub fn fade_ms(&self) -> u64 {
+        self.fade.get().as_millis() as u64
+    }
+
+    pub fn set_fade_ms(&self, fade_ms: u64) {
+        self.fade.set(Duration::from_millis(fade_ms));
+    }
+
+    pub fn plot(&self, plot: Plot) {
+        let mut plots = self.plots.borrow_mut();
+        if !plots
+            .iter()
+            .any(|p| p.address == plot.address && p.kind == plot.kind)
+        {
+            plots.push(plot);
+        }
+    }
+
+    pub fn unplot(&self, index: usize) {
+        self.plots.borrow_mut().remove(index);
+    }
+
+    pub fn plots(&self) -> Vec<Plot> {
+        self.plots.borrow().clone()
+    }
+}
+
+/// Converts bytes of the field into a number that can be plotted.
+fn decode(kind: FieldKind, bytes: &[u8]) -> Option<f64> {
+    let mut buf = [0; 8];
+    buf[..bytes.len().min(8)].copy_from_slice(&bytes[..bytes.len().min(8)]);
+
+    Some(match kind {
+        FieldKind::I8 => buf[0] as i8 as f64,
+        FieldKind::I16 => i16::from_le_bytes([buf[0], buf[1]]) as f64,
+        FieldKind::I32 | FieldKind::RelPtr32 => {
+            i32::from_le_bytes(buf[..4].try_into().unwrap()) as f64
+        }
+        FieldKind::I64 | FieldKind::RelPtr64 => i64::from_le_bytes(buf) as f64,
+        FieldKind::U8 | FieldKind::Bool | FieldKind::Unk8 => buf[0] as f64,
+        FieldKind::U16 | FieldKind::Unk16 => u16::from_le_bytes([buf[0], buf[1]]) as f64,
+        FieldKind::U32 | FieldKind::Unk32 => {
+            u32::from_le_bytes(buf[..4].try_into().unwrap()) as f64
+        }
+        FieldKind::U64
+        | FieldKind::Unk64
+        | FieldKind::Ptr
+        | FieldKind::StrPtr
+        | FieldKind::FnPtr => u64::from_le_bytes(buf) as f64,
+        FieldKind::F32 => f32::from_le_bytes(buf[..4].try_into().unwrap()) as f64,
+        FieldKind::F64 => f64::from_le_bytes(buf),
+        FieldKind::Union => return None,
+    })
+}
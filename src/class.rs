@@ -1,13 +1,51 @@
-use crate::field::{Field, FieldKind, HexField};
-use std::{cell::Cell, iter::repeat_with};
+use crate::{
+    address::parse_address,
+    app::is_valid_ident,
+    field::{Field, FieldId, FieldKind, HexField},
+};
+use serde::{Deserialize, Serialize};
+use std::{cell::Cell, collections::BTreeSet, fmt, iter::repeat_with};
 
 pub type ClassId = usize;
 
+/// Named object of the class, e.g. `Local player` at `0x7FF61A2B0`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Instance {
+    pub label: String,
+    /// Address expression of the object.
+    pub address: String,
+}
+
+/// Place where a class is referenced.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reference {
+    pub class_id: ClassId,
+    /// Field pointing to the class, `None` if the class is derived from it.
+    pub field_id: Option<FieldId>,
+}
+
+impl Instance {
+    pub fn resolve(&self) -> Option<usize> {
+        parse_address(&self.address)
+    }
+}
+
 pub struct Class {
     id: ClassId,
     pub name: String,
     pub address: Cell<usize>,
     pub fields: Vec<Box<dyn Field>>,
+    /// Fields of the base class are placed before fields of this class.
+    pub base: Option<ClassId>,
+    pub instances: Vec<Instance>,
+    /// Namespace path separated with `::`, empty for the global namespace.
+    pub namespace: String,
+    /// Declared size including the base class, `None` if it is the size of the fields.
+    pub size: Option<usize>,
+    /// Declared alignment, `None` for the natural alignment of the fields.
+    pub alignment: Option<usize>,
+    /// Free-text note of the user.
+    pub note: String,
 }
 
 impl Class {
@@ -19,6 +57,12 @@ impl Class {
                 .take(10)
                 .collect(),
             address: 0.into(),
+            base: None,
+            instances: vec![],
+            namespace: String::new(),
+            size: None,
+            alignment: None,
+            note: String::new(),
         }
     }
 
@@ -28,12 +72,100 @@ impl Class {
             name,
             fields: vec![],
             address: 0.into(),
+            base: None,
+            instances: vec![],
+            namespace: String::new(),
+            size: None,
+            alignment: None,
+            note: String::new(),
         }
     }
 
     pub fn id(&self) -> usize {
         self.id
     }
+
+    pub fn base<'a>(&self, classes: &'a [Class]) -> Option<&'a Class> {
+        self.base.and_then(|id| classes.iter().find(|c| c.id == id))
+    }
+
+    /// Offset of the field at `pos` from the start of own fields.
+    pub fn offset_of(&self, pos: usize) -> usize {
+        self.fields.iter().take(pos).map(|f| f.size()).sum()
+    }
+
+    /// Size of own fields, without the base class.
+    pub fn own_size(&self) -> usize {
+        self.fields.iter().map(|f| f.size()).sum()
+    }
+}
+
+/// Problem with the layout of a class found by [`ClassList::layout_issues`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayoutIssue {
+    /// Fields take more space than the declared size.
+    Oversized { used: usize, size: usize },
+    /// Fields take less space than the declared size.
+    Undersized { used: usize, size: usize },
+    /// Size of the class isn't a multiple of its alignment.
+    UnalignedSize { size: usize, alignment: usize },
+    /// Named field is placed at an offset that isn't aligned for its kind.
+    Misaligned {
+        name: String,
+        offset: usize,
+        alignment: usize,
+    },
+}
+
+impl fmt::Display for LayoutIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Oversized { used, size } => write!(
+                f,
+                "Fields take 0x{used:X} bytes, more than the declared size of 0x{size:X}"
+            ),
+            Self::Undersized { used, size } => write!(
+                f,
+                "Fields take 0x{used:X} bytes, less than the declared size of 0x{size:X}"
+            ),
+            Self::UnalignedSize { size, alignment } => write!(
+                f,
+                "Size 0x{size:X} isn't a multiple of the alignment {alignment}"
+            ),
+            Self::Misaligned {
+                name,
+                offset,
+                alignment,
+            } => write!(
+                f,
+                "{name} at 0x{offset:X} isn't aligned to {alignment} bytes"
+            ),
+        }
+    }
+}
+
+/// Returns `false` unless every part of the namespace is a valid identifier.
+/// Empty namespace is the global one.
+pub fn is_valid_namespace(namespace: &str) -> bool {
+    namespace.is_empty() || namespace.split("::").all(is_valid_ident)
+}
+
+/// Namespace `child` nested into `parent`.
+pub fn join_namespace(parent: &str, child: &str) -> String {
+    match parent {
+        "" => child.to_owned(),
+        parent => format!("{parent}::{child}"),
+    }
+}
+
+/// First part of `namespace` after `parent`, e.g. `b` for `a::b::c` inside of `a`.
+/// `None` if the namespace isn't nested into `parent`.
+pub fn child_namespace<'a>(parent: &str, namespace: &'a str) -> Option<&'a str> {
+    let rest = match parent {
+        "" => namespace,
+        parent => namespace.strip_prefix(parent)?.strip_prefix("::")?,
+    };
+    rest.split("::").next().filter(|c| !c.is_empty())
 }
 
 pub struct ClassList {
@@ -60,19 +192,20 @@ impl ClassList {
         &self.classes[..]
     }
 
-    pub fn classes_mut(&mut self) -> &mut [Class] {
-        &mut self.classes[..]
-    }
-
-    pub fn remove_empty(&mut self) {
-        self.classes.retain(|c| {
-            !c.fields.iter().all(|f| {
-                f.kind() == FieldKind::Unk8
-                    || f.kind() == FieldKind::Unk16
-                    || f.kind() == FieldKind::Unk32
-                    || f.kind() == FieldKind::Unk64
+    /// Ids of classes that only consist of hex fields.
+    pub fn empty_classes(&self) -> Vec<ClassId> {
+        self.classes
+            .iter()
+            .filter(|c| {
+                c.fields.iter().all(|f| {
+                    f.kind() == FieldKind::Unk8
+                        || f.kind() == FieldKind::Unk16
+                        || f.kind() == FieldKind::Unk32
+                        || f.kind() == FieldKind::Unk64
+                })
             })
-        });
+            .map(|c| c.id)
+            .collect()
     }
 
     pub fn add_empty_class(&mut self, name: String) -> usize {
@@ -108,12 +241,165 @@ impl ClassList {
         self.classes.iter_mut().find(|c| c.id == id)
     }
 
+    /// Class that contains the field.
+    pub fn by_field(&self, field_id: FieldId) -> Option<&Class> {
+        self.classes
+            .iter()
+            .find(|c| c.fields.iter().any(|f| f.id() == field_id))
+    }
+
     pub fn by_name(&self, name: &str) -> Option<&Class> {
         self.classes.iter().find(|c| c.name == name)
     }
 
-    pub fn delete_by_id(&mut self, id: usize) {
-        self.classes.retain(|c| c.id != id);
+    /// Removes the class and returns it together with its position in the list.
+    pub fn remove(&mut self, id: usize) -> Option<(usize, Class)> {
+        let index = self.classes.iter().position(|c| c.id == id)?;
+        Some((index, self.classes.remove(index)))
+    }
+
+    pub fn insert(&mut self, index: usize, class: Class) {
+        self.classes.insert(index.min(self.classes.len()), class);
+    }
+
+    /// Base classes of the class, starting from the root of the hierarchy.
+    pub fn bases(&self, id: ClassId) -> Vec<&Class> {
+        let mut bases = vec![];
+
+        let mut current = self.by_id(id).and_then(|c| c.base);
+        while let Some(base) = current.and_then(|b| self.by_id(b)) {
+            // Cycles can't be created through `set_base`, but better be safe.
+            if bases.len() == self.classes.len() {
+                break;
+            }
+
+            bases.push(base);
+            current = base.base;
+        }
+
+        bases.reverse();
+        bases
+    }
+
+    /// Offset at which own fields of the class start.
+    pub fn base_size(&self, id: ClassId) -> usize {
+        self.bases(id).iter().map(|b| b.own_size()).sum()
+    }
+
+    /// Declared size of the class or the size of its fields including the base class.
+    pub fn size_of(&self, id: ClassId) -> usize {
+        match self.by_id(id) {
+            Some(class) => class
+                .size
+                .unwrap_or_else(|| self.base_size(id) + class.own_size()),
+            None => 0,
+        }
+    }
+
+    /// Declared alignment of the class or the largest alignment of its named fields.
+    pub fn alignment_of(&self, id: ClassId) -> usize {
+        let Some(class) = self.by_id(id) else {
+            return 1;
+        };
+
+        class.alignment.unwrap_or_else(|| {
+            self.bases(id)
+                .into_iter()
+                .chain([class])
+                .flat_map(|c| c.fields.iter())
+                .filter(|f| f.name().is_some())
+                .map(|f| f.alignment())
+                .max()
+                .unwrap_or(1)
+        })
+    }
+
+    /// Checks own fields of the class against its declared size and alignment.
+    pub fn layout_issues(&self, id: ClassId) -> Vec<LayoutIssue> {
+        let Some(class) = self.by_id(id) else {
+            return vec![];
+        };
+
+        let mut issues = vec![];
+        let base_size = self.base_size(id);
+        let used = base_size + class.own_size();
+        match class.size {
+            Some(size) if used > size => issues.push(LayoutIssue::Oversized { used, size }),
+            Some(size) if used < size => issues.push(LayoutIssue::Undersized { used, size }),
+            _ => {}
+        }
+
+        let (size, alignment) = (self.size_of(id), self.alignment_of(id));
+        if !size.is_multiple_of(alignment) {
+            issues.push(LayoutIssue::UnalignedSize { size, alignment });
+        }
+
+        let mut offset = base_size;
+        for field in class.fields.iter() {
+            if let Some(name) = field.name() {
+                let alignment = field.alignment();
+                if !offset.is_multiple_of(alignment) {
+                    issues.push(LayoutIssue::Misaligned {
+                        name,
+                        offset,
+                        alignment,
+                    });
+                }
+            }
+            offset += field.size();
+        }
+
+        issues
+    }
+
+    /// Returns `false` if the base would create a cycle.
+    pub fn can_set_base(&self, id: ClassId, base: ClassId) -> bool {
+        id != base && !self.bases(base).iter().any(|b| b.id == id)
+    }
+
+    /// Classes ordered so that every base class comes before the classes derived from it.
+    pub fn in_base_order(&self) -> Vec<&Class> {
+        let mut ordered = self.classes.iter().collect::<Vec<_>>();
+        ordered.sort_by_key(|c| self.bases(c.id).len());
+        ordered
+    }
+
+    /// Fields pointing to the class and classes derived from it.
+    pub fn references(&self, id: ClassId) -> Vec<Reference> {
+        let mut references = vec![];
+        for class in self.classes.iter() {
+            if class.base == Some(id) {
+                references.push(Reference {
+                    class_id: class.id,
+                    field_id: None,
+                });
+            }
+
+            references.extend(
+                class
+                    .fields
+                    .iter()
+                    .filter(|f| f.points_to(id))
+                    .map(|f| Reference {
+                        class_id: class.id,
+                        field_id: Some(f.id()),
+                    }),
+            );
+        }
+        references
+    }
+
+    /// Namespaces of all classes together with the namespaces they are nested into, sorted.
+    pub fn namespaces(&self) -> Vec<String> {
+        let mut namespaces = BTreeSet::new();
+        for class in self.classes.iter().filter(|c| !c.namespace.is_empty()) {
+            let mut path = String::new();
+            for part in class.namespace.split("::") {
+                path = join_namespace(&path, part);
+                namespaces.insert(path.clone());
+            }
+        }
+        namespaces.into_iter().collect()
     }
 
     pub fn selected_class(&self) -> Option<&Class> {
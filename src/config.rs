@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, fs, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+};
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct YClassConfig {
@@ -7,6 +11,12 @@ pub struct YClassConfig {
 
     pub recent_projects: Option<HashSet<PathBuf>>,
     pub dpi: Option<f32>,
+    /// How often frozen values are rewritten.
+    pub freeze_interval_ms: Option<u64>,
+    /// How long changed values stay highlighted.
+    pub highlight_fade_ms: Option<u64>,
+    /// Read-only mode of every memflow connector it was toggled for.
+    pub read_only_connectors: Option<HashMap<String, bool>>,
 }
 
 impl YClassConfig {
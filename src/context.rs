@@ -1,9 +1,14 @@
 use crate::{
-    class::{ClassId, ClassList},
-    field::FieldId,
+    changes::ChangeTracker,
+    class::{Class, ClassId, ClassList},
+    field::{FieldId, FieldResponse},
+    freeze::FreezeList,
+    guess::Guesses,
+    history::{Command, History},
     process::YProcess,
+    watch::{WatchList, WatchPath},
 };
-use eframe::egui::Id;
+use eframe::egui::{Id, Modifiers, Ui};
 use egui_notify::Toasts;
 use fastrand::Rng;
 
@@ -17,21 +22,61 @@ pub struct InspectionContext<'a> {
 
     pub address: usize,
     pub offset: usize,
+    /// Path from the inspected class to the object at `address`.
+    pub path: WatchPath,
+    /// Modifiers held while fields are clicked.
+    pub modifiers: Modifiers,
 
     pub process: &'a YProcess,
     pub class_list: &'a ClassList,
     pub toasts: &'a mut Toasts,
+    pub history: &'a mut History,
+    pub freezes: &'a FreezeList,
+    pub guesses: &'a Guesses,
+    pub changes: &'a ChangeTracker,
+    pub watches: &'a WatchList,
+    /// [`crate::state::GlobalState::dummy`], cleared when fields are edited
+    /// in a way that is saved in the project, e.g. their notes.
+    pub dummy: &'a mut bool,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Selection {
+    /// Address of the field that was selected first.
     pub address: usize,
     pub container_id: ClassId,
     pub field_id: FieldId,
+    /// Address of the class instance selected fields belong to.
+    pub class_address: usize,
+    /// Other selected fields of the same class instance.
+    pub others: Vec<FieldId>,
+}
+
+impl Selection {
+    /// Positions of all selected fields in the class, in ascending order.
+    pub fn positions(&self, class: &Class) -> Vec<usize> {
+        class
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.id() == self.field_id || self.others.contains(&f.id()))
+            .map(|(i, _)| i)
+            .collect()
+    }
 }
 
 impl InspectionContext<'_> {
     pub fn select(&mut self, field_id: FieldId) {
+        // Shift-click selects all fields between the first selected field and the clicked one.
+        if self.modifiers.shift && self.select_range(field_id) {
+            return;
+        }
+
+        // Ctrl-click adds the field to the selection or removes it.
+        if self.modifiers.command && self.toggle_selected(field_id) {
+            return;
+        }
+
         if self.is_selected(field_id) {
             self.selection = None;
         } else {
@@ -39,19 +84,123 @@ impl InspectionContext<'_> {
                 container_id: self.current_container,
                 address: self.address + self.offset,
                 field_id,
+                class_address: self.address,
+                others: vec![],
+            });
+        }
+    }
+
+    fn toggle_selected(&mut self, field_id: FieldId) -> bool {
+        let (class_list, container, address) =
+            (self.class_list, self.current_container, self.address);
+        let Some(selection) = self
+            .selection
+            .as_mut()
+            .filter(|s| s.container_id == container && s.class_address == address)
+        else {
+            return false;
+        };
+
+        if let Some(i) = selection.others.iter().position(|id| *id == field_id) {
+            selection.others.remove(i);
+        } else if selection.field_id != field_id {
+            selection.others.push(field_id);
+        } else {
+            // The first selected field is replaced with the next one.
+            let Some(class) = class_list.by_id(container) else {
+                return false;
+            };
+            let Some(pos) = class
+                .fields
+                .iter()
+                .position(|f| selection.others.contains(&f.id()))
+            else {
+                return false;
+            };
+
+            let next = class.fields[pos].id();
+            selection.others.retain(|id| *id != next);
+            selection.field_id = next;
+            selection.address = address + class_list.base_size(container) + class.offset_of(pos);
+        }
+
+        true
+    }
+
+    fn select_range(&mut self, field_id: FieldId) -> bool {
+        let (class_list, container, address) =
+            (self.class_list, self.current_container, self.address);
+        let Some(selection) = self
+            .selection
+            .as_mut()
+            .filter(|s| s.container_id == container && s.class_address == address)
+        else {
+            return false;
+        };
+        let Some(class) = class_list.by_id(container) else {
+            return false;
+        };
+
+        let position = |id| class.fields.iter().position(|f| f.id() == id);
+        let (Some(first), Some(clicked)) = (position(selection.field_id), position(field_id))
+        else {
+            return false;
+        };
+
+        selection.others = class.fields[first.min(clicked)..=first.max(clicked)]
+            .iter()
+            .map(|f| f.id())
+            .filter(|id| *id != selection.field_id)
+            .collect();
+        true
+    }
+
+    /// Draws fields of the class. Fields inherited from base classes are drawn first
+    /// and can only be edited in the base class itself.
+    pub fn draw_class(&mut self, ui: &mut Ui, class: &Class) -> Option<FieldResponse> {
+        let (class_list, container) = (self.class_list, self.current_container);
+
+        for base in class_list.bases(class.id()) {
+            self.current_container = base.id();
+            ui.add_enabled_ui(false, |ui| {
+                for field in base.fields.iter() {
+                    self.current_id = Id::new(self.level_rng.u64(..));
+                    field.draw(ui, self);
+                }
             });
         }
+        self.current_container = container;
+
+        class.fields.iter().fold(None, |r, f| {
+            self.current_id = Id::new(self.level_rng.u64(..));
+            r.or(f.draw(ui, self))
+        })
+    }
+
+    /// Writes `bytes` at `address` remembering previous bytes to be able to undo it.
+    /// Returns `false` if the write was blocked by the read-only mode or failed.
+    pub fn write(&mut self, address: usize, bytes: &[u8]) -> bool {
+        let mut old = vec![0; bytes.len()];
+        self.process.read(address, &mut old);
+        if !self.process.write(address, bytes) {
+            self.toasts.error(self.process.write_error());
+            return false;
+        }
+
+        self.history.push(Command::Write {
+            pid: self.process.id(),
+            address,
+            bytes: old,
+        });
+        true
     }
 
     pub fn is_selected(&self, field_id: FieldId) -> bool {
-        self.selection
-            .as_ref()
-            .map(|s| s.address == self.address + self.offset)
-            .unwrap_or(false)
-            && self
-                .selection
-                .as_ref()
-                .map(|s| s.field_id == field_id)
-                .unwrap_or(false)
+        self.selection.as_ref().is_some_and(|s| {
+            (s.field_id == field_id && s.address == self.address + self.offset)
+                || (s.others.contains(&field_id)
+                    && s.class_address == self.address
+                    && s.container_id == self.current_container)
+        })
     }
 }
@@ -1,6 +1,7 @@
 use super::{
-    display_field_name, display_field_prelude, display_field_value, next_id, CodegenData, Field,
-    FieldId, FieldKind, FieldResponse, NamedState,
+    display_field_name, display_field_prelude, display_field_value, freeze_menu, history_menu,
+    next_id, note_menu, watch_menu, CodegenData, Field, FieldId, FieldKind, FieldResponse,
+    NamedState,
 };
 use crate::{context::InspectionContext, generator::Generator};
 use eframe::{
@@ -32,6 +33,10 @@ impl Field for BoolField {
         Some(self.state.name.borrow().clone())
     }
 
+    fn named_state(&self) -> Option<&NamedState> {
+        Some(&self.state)
+    }
+
     fn size(&self) -> usize {
         1
     }
@@ -49,9 +54,16 @@ impl Field for BoolField {
             let mut job = LayoutJob::default();
             display_field_prelude(ui.ctx(), self, ctx, &mut job);
 
-            if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+            let r = ui.add(Label::new(job).sense(Sense::click()));
+            if r.clicked() {
                 ctx.select(self.id);
             }
+            r.context_menu(|ui| {
+                freeze_menu(ui, ctx, &self.state, 1);
+                history_menu(ui, ctx, &self.state, self.kind());
+                watch_menu(ui, ctx, &self.state, self.kind());
+                note_menu(ui, ctx, &self.state);
+            });
 
             display_field_name(self, ui, ctx, &self.state, Color32::GOLD);
             display_field_value(
@@ -69,15 +81,9 @@ impl Field for BoolField {
                     .to_owned()
                 },
                 |new: &str| match new {
-                    "1" | "true" | "yes" | "on" => {
-                        ctx.process.write(address, &[1]);
-                        true
-                    }
-                    "0" | "false" | "no" | "off" => {
-                        ctx.process.write(address, &[0]);
-                        true
-                    }
-                    _ => false,
+                    "1" | "true" | "yes" | "on" => Some(vec![1]),
+                    "0" | "false" | "no" | "off" => Some(vec![0]),
+                    _ => None,
                 },
             );
         });
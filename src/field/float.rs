@@ -1,5 +1,6 @@
 use super::{
-    display_field_name, display_field_prelude, display_field_value, next_id, CodegenData, Field,
+    display_field_name, display_field_prelude, display_field_value, format_menu, freeze_menu,
+    history_menu, next_id, note_menu, parse_float, watch_menu, CodegenData, DisplayFormat, Field,
     FieldId, FieldKind, FieldResponse, NamedState,
 };
 use crate::{context::InspectionContext, generator::Generator};
@@ -35,6 +36,10 @@ impl<const N: usize> Field for FloatField<N> {
         Some(self.state.name.borrow().clone())
     }
 
+    fn named_state(&self) -> Option<&NamedState> {
+        Some(&self.state)
+    }
+
     fn kind(&self) -> FieldKind {
         match N {
             4 => FieldKind::F32,
@@ -52,9 +57,18 @@ impl<const N: usize> Field for FloatField<N> {
             let mut job = LayoutJob::default();
             display_field_prelude(ui.ctx(), self, ctx, &mut job);
 
-            if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+            let r = ui.add(Label::new(job).sense(Sense::click()));
+            if r.clicked() {
                 ctx.select(self.id);
             }
+            r.context_menu(|ui| {
+                format_menu(ui, &self.state, self.kind());
+                ui.separator();
+                freeze_menu(ui, ctx, &self.state, N);
+                history_menu(ui, ctx, &self.state, self.kind());
+                watch_menu(ui, ctx, &self.state, self.kind());
+                note_menu(ui, ctx, &self.state);
+            });
 
             display_field_name(self, ui, ctx, &self.state, Color32::LIGHT_RED);
             display_field_value(
@@ -63,29 +77,29 @@ impl<const N: usize> Field for FloatField<N> {
                 ctx,
                 &self.state,
                 Color32::WHITE,
-                |_| match N {
-                    4 => f32::from_ne_bytes(buf[..].try_into().unwrap()) as f64,
-                    8 => f64::from_ne_bytes(buf[..].try_into().unwrap()),
-                    _ => unreachable!(),
-                },
-                |new| match N {
-                    4 => {
-                        if let Ok(val) = new.parse::<f32>() {
-                            ctx.process.write(address, &val.to_ne_bytes());
-                            true
-                        } else {
-                            false
+                |_| {
+                    let (value, bits) = match N {
+                        4 => {
+                            let bits = u32::from_ne_bytes(buf[..].try_into().unwrap());
+                            (f32::from_bits(bits) as f64, bits as u64)
                         }
-                    }
-                    8 => {
-                        if let Ok(val) = new.parse::<f64>() {
-                            ctx.process.write(address, &val.to_ne_bytes());
-                            true
-                        } else {
-                            false
+                        8 => {
+                            let bits = u64::from_ne_bytes(buf[..].try_into().unwrap());
+                            (f64::from_bits(bits), bits)
                         }
-                    }
-                    _ => false,
+                        _ => unreachable!(),
+                    };
+
+                    self.state
+                        .format
+                        .get()
+                        .unwrap_or(DisplayFormat::Decimal)
+                        .format_float(value, bits)
+                },
+                |new| match (N, parse_float(new, N)) {
+                    (4, Some(val)) => Some((val as f32).to_ne_bytes().to_vec()),
+                    (8, Some(val)) => Some(val.to_ne_bytes().to_vec()),
+                    _ => None,
                 },
             );
         });
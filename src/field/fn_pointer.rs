@@ -0,0 +1,172 @@
+use super::{
+    create_text_format, display_field_name, display_field_prelude, display_field_value,
+    freeze_menu, history_menu, next_id, note_menu, watch_menu, CodegenData, Field, FieldId,
+    FieldKind, FieldResponse, NamedState,
+};
+use crate::{address::parse_address, context::InspectionContext, generator::Generator};
+use eframe::{
+    egui::{Label, Sense, TextEdit, Ui},
+    epaint::{text::LayoutJob, Color32},
+};
+use std::cell::RefCell;
+
+pub struct FnPointerField {
+    id: FieldId,
+    state: NamedState,
+    /// C-style signature of the function, e.g. `int(void*, float)`.
+    signature: RefCell<String>,
+}
+
+impl FnPointerField {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: next_id(),
+            state: NamedState::new(name),
+            signature: String::new().into(),
+        }
+    }
+
+    fn show_menu(&self, ui: &mut Ui, address: usize, response: &mut Option<FieldResponse>) {
+        if ui.button("Open in memory view").clicked() {
+            *response = Some(FieldResponse::ViewMemory(address));
+            ui.close_menu();
+        }
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Signature");
+            ui.add(
+                TextEdit::singleline(&mut *self.signature.borrow_mut())
+                    .desired_width(160.)
+                    .hint_text("void()"),
+            );
+        })
+        .response
+        .on_hover_text("Used when generating code, e.g. `int(void*, float)`");
+    }
+}
+
+impl Field for FnPointerField {
+    fn id(&self) -> FieldId {
+        self.id
+    }
+
+    fn size(&self) -> usize {
+        // TODO: The size of the pointer would be 4 bytes on x86
+        8
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.state.name.borrow().clone())
+    }
+
+    fn named_state(&self) -> Option<&NamedState> {
+        Some(&self.state)
+    }
+
+    fn kind(&self) -> FieldKind {
+        FieldKind::FnPtr
+    }
+
+    fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
+        let mut buf = [0; 8];
+        let paddr = ctx.address + ctx.offset;
+        ctx.process.read(paddr, &mut buf);
+        let address = usize::from_ne_bytes(buf);
+
+        let mut response = None;
+        ui.horizontal(|ui| {
+            let mut job = LayoutJob::default();
+            display_field_prelude(ui.ctx(), self, ctx, &mut job);
+
+            let r = ui.add(Label::new(job).sense(Sense::click()));
+            if r.clicked() {
+                ctx.select(self.id);
+            }
+            r.context_menu(|ui| {
+                self.show_menu(ui, address, &mut response);
+                ui.separator();
+                freeze_menu(ui, ctx, &self.state, 8);
+                history_menu(ui, ctx, &self.state, self.kind());
+                watch_menu(ui, ctx, &self.state, self.kind());
+                note_menu(ui, ctx, &self.state);
+            });
+
+            display_field_name(self, ui, ctx, &self.state, Color32::LIGHT_YELLOW);
+            display_field_value(
+                self,
+                ui,
+                ctx,
+                &self.state,
+                Color32::YELLOW,
+                |edit| {
+                    if edit {
+                        format!("{address:X}")
+                    } else {
+                        format!("-> {address:X}")
+                    }
+                },
+                |new| parse_address(new).map(|addr| addr.to_ne_bytes().to_vec()),
+            );
+
+            if address == 0 {
+                return;
+            }
+
+            let mut job = LayoutJob::default();
+            let selected = ctx.is_selected(self.id);
+            match ctx.process.locate(address) {
+                Some(location) => job.append(
+                    &location.describe(),
+                    4.,
+                    create_text_format(selected, Color32::GRAY),
+                ),
+                None => job.append(
+                    "<unknown>",
+                    4.,
+                    create_text_format(selected, Color32::DARK_GRAY),
+                ),
+            }
+
+            let executable = ctx.process.is_executable(address);
+            if !executable {
+                job.append(
+                    "not executable",
+                    8.,
+                    create_text_format(selected, Color32::RED),
+                );
+            }
+
+            let r = ui.add(Label::new(job).sense(Sense::click()));
+            if r.double_clicked() {
+                response = Some(FieldResponse::ViewMemory(address));
+            } else if r.clicked() {
+                ctx.select(self.id);
+            }
+
+            let r = if executable {
+                r
+            } else {
+                r.on_hover_text("Target isn't in executable memory")
+            };
+            r.context_menu(|ui| self.show_menu(ui, address, &mut response));
+        });
+
+        ctx.offset += self.size();
+        response
+    }
+
+    fn codegen(&self, generator: &mut dyn Generator, _: &CodegenData) {
+        let signature = self.signature.borrow();
+        generator.add_field(
+            self.state.name.borrow().as_str(),
+            FieldKind::FnPtr,
+            Some(signature.trim()).filter(|s| !s.is_empty()),
+        );
+    }
+
+    fn load_metadata(&self, metadata: &str) {
+        *self.signature.borrow_mut() = metadata.to_owned();
+    }
+}
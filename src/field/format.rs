@@ -0,0 +1,233 @@
+//! Display formats of named fields and parsing of values entered in any format.
+use super::{FieldKind, NamedState};
+use eframe::egui::Ui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DisplayFormat {
+    Decimal,
+    Hex,
+    Binary,
+    Char,
+    Scientific,
+}
+
+impl DisplayFormat {
+    /// Formats supported by `kind`, first one is the default.
+    pub fn for_kind(kind: FieldKind) -> &'static [Self] {
+        use FieldKind::*;
+
+        match kind {
+            I8 | I16 | I32 | I64 | U8 | U16 | U32 | U64 => {
+                &[Self::Decimal, Self::Hex, Self::Binary, Self::Char]
+            }
+            F32 | F64 => &[Self::Decimal, Self::Scientific, Self::Hex],
+            _ => &[],
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Decimal => "Decimal",
+            Self::Hex => "Hex",
+            Self::Binary => "Binary",
+            Self::Char => "Char",
+            Self::Scientific => "Scientific",
+        }
+    }
+
+    /// Formats integer of `size` bytes, `bits` are raw bits of the value.
+    pub fn format_int(&self, bits: u64, size: usize, signed: bool) -> String {
+        let width = size * 8;
+        let bits = if width == 64 {
+            bits
+        } else {
+            bits & ((1 << width) - 1)
+        };
+
+        match self {
+            Self::Hex => format!("0x{bits:X}"),
+            Self::Binary => format!("0b{bits:0width$b}"),
+            Self::Char => match char::from_u32(bits as u32) {
+                Some(c) if bits <= u32::MAX as u64 && !c.is_control() => format!("{c:?}"),
+                _ => format!("0x{bits:X}"),
+            },
+            Self::Decimal | Self::Scientific if signed => {
+                // Sign extends the value.
+                let shift = 64 - width;
+                (((bits << shift) as i64) >> shift).to_string()
+            }
+            Self::Decimal | Self::Scientific => bits.to_string(),
+        }
+    }
+
+    pub fn format_float(&self, value: f64, bits: u64) -> String {
+        match self {
+            Self::Scientific => format!("{value:e}"),
+            Self::Hex => format!("0x{bits:X}"),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// Context menu to pick the display format of the field of `kind`.
+pub fn format_menu(ui: &mut Ui, state: &NamedState, kind: FieldKind) {
+    let formats = DisplayFormat::for_kind(kind);
+    if formats.is_empty() {
+        return;
+    }
+
+    ui.menu_button("Format", |ui| {
+        let current = state.format.get().unwrap_or(formats[0]);
+        for format in formats {
+            if ui
+                .selectable_label(current == *format, format.label())
+                .clicked()
+            {
+                // Default format isn't stored.
+                state.format.set(Some(*format).filter(|f| *f != formats[0]));
+                ui.close_menu();
+            }
+        }
+    });
+}
+
+/// Parses integer written in decimal, hex(`0x`), binary(`0b`), octal(`0o`)
+/// or as a character literal(`'a'`).
+pub fn parse_int(text: &str) -> Option<i128> {
+    let text = text.trim();
+
+    if let Some(c) = text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) {
+        let mut chars = c.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c as i128),
+            _ => None,
+        };
+    }
+
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let text = text.replace('_', "");
+
+    let (digits, radix) = if let Some(hex) = text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+        (hex, 16)
+    } else if let Some(bin) = text.strip_prefix("0b").or(text.strip_prefix("0B")) {
+        (bin, 2)
+    } else if let Some(oct) = text.strip_prefix("0o").or(text.strip_prefix("0O")) {
+        (oct, 8)
+    } else {
+        (text.as_str(), 10)
+    };
+
+    // Only the leading `-` is a sign, `--5` or `0x-5` aren't numbers.
+    if digits.starts_with(['+', '-']) {
+        return None;
+    }
+    let value = i128::from_str_radix(digits, radix).ok()?;
+
+    Some(if negative { -value } else { value })
+}
+
+/// Converts `value` into bytes of an integer of `size` bytes.
+/// Both signed and unsigned representations are accepted, so `-1` and `0xFF` are the same `u8`.
+pub fn int_to_bytes(value: i128, size: usize) -> Option<Vec<u8>> {
+    let width = size as u32 * 8;
+    let (min, max) = (-(1i128 << (width - 1)), (1i128 << width) - 1);
+
+    if value < min || value > max {
+        return None;
+    }
+
+    Some(value.to_le_bytes()[..size].to_vec())
+}
+
+/// Parses a float of `size` bytes, raw bits can be written as hex(`0x`).
+pub fn parse_float(text: &str, size: usize) -> Option<f64> {
+    let text = text.trim();
+
+    match text.strip_prefix("0x") {
+        Some(hex) if size == 4 => u32::from_str_radix(hex, 16)
+            .ok()
+            .map(|b| f32::from_bits(b) as f64),
+        Some(hex) => u64::from_str_radix(hex, 16).ok().map(f64::from_bits),
+        None => text.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ints() {
+        for (text, expected) in [
+            ("42", Some(42)),
+            (" -42 ", Some(-42)),
+            ("1_000", Some(1000)),
+            ("0xFF", Some(0xFF)),
+            ("0Xff", Some(0xFF)),
+            ("-0x10", Some(-0x10)),
+            ("0b101", Some(0b101)),
+            ("0o17", Some(0o17)),
+            ("'a'", Some('a' as i128)),
+            ("'ж'", Some('ж' as i128)),
+            ("0xFFFFFFFFFFFFFFFF", Some(u64::MAX as i128)),
+            ("", None),
+            ("-", None),
+            ("--5", None),
+            ("0x-5", None),
+            ("0b102", None),
+            ("'ab'", None),
+            ("''", None),
+            ("1.5", None),
+        ] {
+            assert_eq!(parse_int(text), expected, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn converts_ints_to_bytes() {
+        for (value, size, expected) in [
+            (0, 1, Some(vec![0])),
+            (-1, 1, Some(vec![0xFF])),
+            (0xFF, 1, Some(vec![0xFF])),
+            (-128, 1, Some(vec![0x80])),
+            (256, 1, None),
+            (-129, 1, None),
+            (0x1234, 2, Some(vec![0x34, 0x12])),
+            (-1, 4, Some(vec![0xFF; 4])),
+            (u32::MAX as i128 + 1, 4, None),
+            (u64::MAX as i128, 8, Some(vec![0xFF; 8])),
+            (i64::MIN as i128, 8, Some(i64::MIN.to_le_bytes().to_vec())),
+            (u64::MAX as i128 + 1, 8, None),
+        ] {
+            assert_eq!(
+                int_to_bytes(value, size),
+                expected,
+                "{value} in {size} bytes"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_floats() {
+        for (text, size, expected) in [
+            ("1.5", 4, Some(1.5)),
+            (" -2 ", 8, Some(-2.)),
+            ("1e3", 8, Some(1000.)),
+            ("0x3F800000", 4, Some(1.)),
+            ("0x3FF0000000000000", 8, Some(1.)),
+            ("0x3FF0000000000000", 4, None),
+            ("0xZZ", 8, None),
+            ("one", 4, None),
+        ] {
+            assert_eq!(
+                parse_float(text, size),
+                expected,
+                "{text:?} of {size} bytes"
+            );
+        }
+    }
+}
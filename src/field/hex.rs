@@ -1,14 +1,18 @@
 use super::{
-    create_text_format, display_field_prelude, next_id, CodegenData, Field, FieldId, FieldKind,
-    FieldResponse,
+    change_highlight, color_swatch, create_text_format, display_field_prelude, next_id,
+    semantic_menu, CodegenData, Field, FieldId, FieldKind, FieldResponse, Semantic,
 };
 use crate::{context::InspectionContext, generator::Generator};
 use eframe::{
-    egui::{Label, ScrollArea, Sense, Ui},
+    egui::{Label, RichText, ScrollArea, Sense, Ui},
     epaint::{text::LayoutJob, Color32},
 };
 use once_cell::unsync::Lazy;
-use std::{cell::RefCell, iter::repeat_with, ops::RangeFrom};
+use std::{
+    cell::{Cell, RefCell},
+    iter::repeat_with,
+    ops::RangeFrom,
+};
 
 struct PreviewState {
     address: usize,
@@ -38,6 +42,8 @@ impl PreviewState {
 
 pub struct HexField<const N: usize> {
     preview_state: RefCell<Option<PreviewState>>,
+    /// Stored in the project as an unknown field with the semantic in its metadata.
+    semantic: Cell<Option<Semantic>>,
     id: FieldId,
 }
 
@@ -46,10 +52,12 @@ impl<const N: usize> HexField<N> {
         Self {
             id: next_id(),
             preview_state: None.into(),
+            semantic: None.into(),
         }
     }
 
     fn byte_view(&self, ctx: &mut InspectionContext, job: &mut LayoutJob, buf: &[u8; N]) {
+        let heat = ctx.changes.observe(ctx.address + ctx.offset, buf);
         for (i, b) in buf.iter().enumerate() {
             let rng = fastrand::Rng::with_seed(*b as _);
             let color = if *b == 0 {
@@ -59,11 +67,11 @@ impl<const N: usize> HexField<N> {
                 Color32::from_rgb(rng.u8(MIN), rng.u8(MIN), rng.u8(MIN))
             };
 
-            job.append(
-                &format!("{b:02X}"),
-                4. + if i == 0 { 4. } else { 0. },
-                create_text_format(ctx.is_selected(self.id), color),
-            );
+            let mut tf = create_text_format(ctx.is_selected(self.id), color);
+            if heat[i] > 0. {
+                tf.background = change_highlight(heat[i]);
+            }
+            job.append(&format!("{b:02X}"), 4. + if i == 0 { 4. } else { 0. }, tf);
         }
     }
 
@@ -138,6 +146,60 @@ impl<const N: usize> HexField<N> {
         }
     }
 
+    fn semantic_view(&self, ui: &mut Ui, ctx: &mut InspectionContext) {
+        let Some(semantic) = self.semantic.get() else {
+            return;
+        };
+
+        let mut buf = vec![0; semantic.read_size(N)];
+        ctx.process.read(ctx.address + ctx.offset, &mut buf);
+
+        let mut job = LayoutJob::default();
+        job.append(
+            &semantic.format(&buf, true),
+            4.,
+            create_text_format(ctx.is_selected(self.id), Color32::KHAKI),
+        );
+
+        let r = ui.add(Label::new(job).sense(Sense::click()));
+        if r.clicked() {
+            ctx.select(self.id);
+        }
+        r.on_hover_text(semantic.label());
+
+        if let Some(color) = semantic.color(&buf) {
+            color_swatch(ui, color);
+        }
+    }
+
+    fn guess_view(
+        &self,
+        ui: &mut Ui,
+        ctx: &mut InspectionContext,
+        response: &mut Option<FieldResponse>,
+    ) {
+        let Some(guess) = ctx.guesses.get(&self.id) else {
+            return;
+        };
+
+        let r = ui
+            .add(
+                Label::new(
+                    RichText::new(format!("{}?", guess.describe()))
+                        .italics()
+                        .color(Color32::GRAY),
+                )
+                .sense(Sense::click()),
+            )
+            .on_hover_text("Click to accept the guess, right click to dismiss it");
+
+        if r.clicked() {
+            *response = Some(FieldResponse::AcceptGuess(self.id));
+        } else if r.secondary_clicked() {
+            *response = Some(FieldResponse::DismissGuess(self.id));
+        }
+    }
+
     fn pointer_view(
         &self,
         ui: &mut Ui,
@@ -248,13 +310,17 @@ impl<const N: usize> Field for HexField<N> {
             display_field_prelude(ui.ctx(), self, ctx, &mut job);
             self.byte_view(ctx, &mut job, &buf);
 
-            if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+            let r = ui.add(Label::new(job).sense(Sense::click()));
+            if r.clicked() {
                 ctx.select(self.id);
             }
+            r.context_menu(|ui| semantic_menu(ui, &self.semantic, N, true));
 
             self.int_view(ui, ctx, &buf);
             self.float_view(ui, ctx, &buf);
             self.pointer_view(ui, ctx, &buf, &mut response);
+            self.semantic_view(ui, ctx);
+            self.guess_view(ui, ctx, &mut response);
         });
 
         ctx.offset += N;
@@ -264,6 +330,15 @@ impl<const N: usize> Field for HexField<N> {
     fn codegen(&self, generator: &mut dyn Generator, _: &CodegenData) {
         generator.add_offset(self.size());
     }
+
+    fn load_metadata(&self, metadata: &str) {
+        self.semantic
+            .set(Semantic::from_metadata(metadata).filter(|s| s.supports(N)));
+    }
+
+    fn semantic(&self) -> Option<Semantic> {
+        self.semantic.get()
+    }
 }
 
 fn int_high_low_from_le<const N: usize>(high: &[u8], low: &[u8]) -> (i64, i64) {
@@ -1,17 +1,21 @@
 use super::{
-    display_field_name, display_field_prelude, display_field_value, next_id, CodegenData, Field,
-    FieldId, FieldKind, FieldResponse, NamedState,
+    color_swatch, display_field_name, display_field_prelude, display_field_value, format_menu,
+    freeze_menu, history_menu, int_to_bytes, next_id, note_menu, parse_int, semantic_menu,
+    watch_menu, CodegenData, DisplayFormat, Field, FieldId, FieldKind, FieldResponse, NamedState,
+    Semantic,
 };
-use crate::{context::InspectionContext, generator::Generator, process::YProcess};
+use crate::{context::InspectionContext, generator::Generator};
 use eframe::{
     egui::{Label, Sense, Ui},
     epaint::{text::LayoutJob, Color32},
 };
+use std::cell::Cell;
 
 pub struct IntField<const N: usize> {
     id: FieldId,
     signed: bool,
     state: NamedState,
+    semantic: Cell<Option<Semantic>>,
 }
 
 impl<const N: usize> IntField<N> {
@@ -20,6 +24,7 @@ impl<const N: usize> IntField<N> {
             id: next_id(),
             signed: true,
             state: NamedState::new(name),
+            semantic: None.into(),
         }
     }
 
@@ -28,32 +33,19 @@ impl<const N: usize> IntField<N> {
             id: next_id(),
             signed: false,
             state: NamedState::new(name),
+            semantic: None.into(),
         }
     }
 
-    fn write_value(&self, new: &str, address: usize, proc: &YProcess) -> bool {
-        macro_rules! do_arm {
-            ($buf:ident, $addr:ident, $proc:ident, $new:ident, $type:ty) => {
-                if let Ok(val) = $new.parse::<$type>() {
-                    $proc.write($addr, &val.to_ne_bytes());
-                    true
-                } else {
-                    false
-                }
-            };
-        }
+    fn format_value(&self, buf: &[u8; N]) -> String {
+        let mut bits = [0; 8];
+        bits[..N].copy_from_slice(buf);
 
-        match N {
-            1 if self.signed => do_arm!(buf, address, proc, new, i8),
-            1 if !self.signed => do_arm!(buf, address, proc, new, u8),
-            2 if self.signed => do_arm!(buf, address, proc, new, i16),
-            2 if !self.signed => do_arm!(buf, address, proc, new, u16),
-            4 if self.signed => do_arm!(buf, address, proc, new, i32),
-            4 if !self.signed => do_arm!(buf, address, proc, new, u32),
-            8 if self.signed => do_arm!(buf, address, proc, new, i64),
-            8 if !self.signed => do_arm!(buf, address, proc, new, u64),
-            _ => unreachable!(),
-        }
+        self.state
+            .format
+            .get()
+            .unwrap_or(DisplayFormat::Decimal)
+            .format_int(u64::from_le_bytes(bits), N, self.signed)
     }
 }
 
@@ -70,6 +62,10 @@ impl<const N: usize> Field for IntField<N> {
         Some(self.state.name.borrow().clone())
     }
 
+    fn named_state(&self) -> Option<&NamedState> {
+        Some(&self.state)
+    }
+
     fn kind(&self) -> FieldKind {
         match N {
             1 if self.signed => FieldKind::U8,
@@ -86,16 +82,25 @@ impl<const N: usize> Field for IntField<N> {
 
     fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
         let mut buf = [0; N];
-        let address = ctx.address + ctx.offset;
         ctx.process.read(ctx.address + ctx.offset, &mut buf);
 
         ui.horizontal(|ui| {
             let mut job = LayoutJob::default();
             display_field_prelude(ui.ctx(), self, ctx, &mut job);
 
-            if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+            let r = ui.add(Label::new(job).sense(Sense::click()));
+            if r.clicked() {
                 ctx.select(self.id);
             }
+            r.context_menu(|ui| {
+                format_menu(ui, &self.state, self.kind());
+                semantic_menu(ui, &self.semantic, N, false);
+                ui.separator();
+                freeze_menu(ui, ctx, &self.state, N);
+                history_menu(ui, ctx, &self.state, self.kind());
+                watch_menu(ui, ctx, &self.state, self.kind());
+                note_menu(ui, ctx, &self.state);
+            });
 
             display_field_name(
                 self,
@@ -114,25 +119,16 @@ impl<const N: usize> Field for IntField<N> {
                 ctx,
                 &self.state,
                 Color32::WHITE,
-                |_| match N {
-                    1 if self.signed => (buf[0] as i8).to_string(),
-                    1 if !self.signed => buf[0].to_string(),
-                    2 if self.signed => i16::from_ne_bytes(buf[..].try_into().unwrap()).to_string(),
-                    2 if !self.signed => {
-                        u16::from_ne_bytes(buf[..].try_into().unwrap()).to_string()
-                    }
-                    4 if self.signed => i32::from_ne_bytes(buf[..].try_into().unwrap()).to_string(),
-                    4 if !self.signed => {
-                        u32::from_ne_bytes(buf[..].try_into().unwrap()).to_string()
-                    }
-                    8 if self.signed => i64::from_ne_bytes(buf[..].try_into().unwrap()).to_string(),
-                    8 if !self.signed => {
-                        u64::from_ne_bytes(buf[..].try_into().unwrap()).to_string()
-                    }
-                    _ => unreachable!(),
+                |edit| match self.semantic.get() {
+                    Some(semantic) if !edit => semantic.format(&buf, self.signed),
+                    _ => self.format_value(&buf),
                 },
-                |new| self.write_value(new, address, ctx.process),
+                |new| parse_int(new).and_then(|v| int_to_bytes(v, N)),
             );
+
+            if let Some(color) = self.semantic.get().and_then(|s| s.color(&buf)) {
+                color_swatch(ui, color);
+            }
         });
 
         ctx.offset += N;
@@ -153,7 +149,12 @@ impl<const N: usize> Field for IntField<N> {
                 8 if !self.signed => FieldKind::U64,
                 _ => unreachable!(),
             },
-            None,
+            self.semantic.get().map(Semantic::to_metadata).as_deref(),
         );
     }
+
+    fn load_metadata(&self, metadata: &str) {
+        self.semantic
+            .set(Semantic::from_metadata(metadata).filter(|s| s.supports(N)));
+    }
 }
@@ -1,4 +1,7 @@
-use super::{BoolField, Field, FloatField, HexField, IntField, PointerField, StringPointerField};
+use super::{
+    BoolField, Field, FloatField, FnPointerField, HexField, IntField, PointerField,
+    StringPointerField, UnionField,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -9,8 +12,12 @@ pub enum FieldKind {
     U8, U16, U32, U64,
     F32, F64,
     Ptr,
+    RelPtr32,
+    RelPtr64,
     StrPtr,
+    FnPtr,
     Bool,
+    Union,
 }
 
 impl FieldKind {
@@ -40,9 +47,26 @@ impl FieldKind {
         match self {
             Self::Unk8 | Self::I8 | Self::U8 | Self::Bool => 1,
             Self::Unk16 | Self::I16 | Self::U16 => 2,
-            Self::Unk32 | Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::Unk32 | Self::I32 | Self::U32 | Self::F32 | Self::RelPtr32 => 4,
             // TODO(ItsEthra): Pointer size is... sigh, different for 32-bit processes
-            Self::Unk64 | Self::I64 | Self::U64 | Self::F64 | Self::Ptr | Self::StrPtr => 8,
+            Self::Unk64
+            | Self::I64
+            | Self::U64
+            | Self::F64
+            | Self::Ptr
+            | Self::StrPtr
+            | Self::FnPtr
+            | Self::RelPtr64 => 8,
+            // Size of the default union, actual size depends on its members.
+            Self::Union => 8,
+        }
+    }
+
+    /// Natural alignment of the kind, hex fields and unions don't require any.
+    pub fn alignment(&self) -> usize {
+        match self {
+            Self::Unk8 | Self::Unk16 | Self::Unk32 | Self::Unk64 | Self::Union => 1,
+            other => other.size(),
         }
     }
 
@@ -80,9 +104,15 @@ impl FieldKind {
             )),
             Self::Bool => Box::new(BoolField::new(name.unwrap_or_else(|| "boolean".into()))),
             Self::Ptr => Box::new(PointerField::new(name.unwrap_or_else(|| "pointer".into()))),
+            Self::RelPtr32 | Self::RelPtr64 => Box::new(PointerField::new_relative(
+                name.unwrap_or_else(|| "rel_ptr".into()),
+                self,
+            )),
             Self::StrPtr => Box::new(StringPointerField::new(
                 name.unwrap_or_else(|| "str_ptr".into()),
             )),
+            Self::FnPtr => Box::new(FnPointerField::new(name.unwrap_or_else(|| "fn_ptr".into()))),
+            Self::Union => Box::new(UnionField::new(name.unwrap_or_else(|| "union".into()))),
         }
     }
 }
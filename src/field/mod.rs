@@ -14,8 +14,21 @@ mod string_pointer;
 pub use string_pointer::*;
 mod boolean;
 pub use boolean::*;
+mod union;
+pub use union::*;
+mod semantic;
+pub use semantic::*;
+mod format;
+pub use format::*;
+mod fn_pointer;
+pub use fn_pointer::*;
 
-use crate::{class::Class, context::InspectionContext, generator::Generator, FID_M};
+use crate::{
+    class::{Class, ClassId},
+    context::InspectionContext,
+    generator::Generator,
+    FID_M,
+};
 use eframe::{
     egui::{Id, TextFormat, Ui},
     epaint::{Color32, Stroke},
@@ -28,6 +41,13 @@ pub enum FieldResponse {
     NewClass(String, usize),
     LockScroll,
     UnlockScroll,
+    /// Opens memory view at the address.
+    ViewMemory(usize),
+    /// Replaces the field with the field of the guessed kind.
+    AcceptGuess(FieldId),
+    DismissGuess(FieldId),
+    /// Opens the class at the address in a new inspector tab.
+    OpenTab(ClassId, usize),
 }
 
 pub trait Field {
@@ -36,8 +56,62 @@ pub trait Field {
     fn size(&self) -> usize;
     fn kind(&self) -> FieldKind;
 
+    /// Alignment the offset of the field is expected to have.
+    fn alignment(&self) -> usize {
+        self.kind().alignment()
+    }
+
     fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse>;
     fn codegen(&self, generator: &mut dyn Generator, data: &CodegenData);
+
+    /// Generates the field preceded by its note as a comment.
+    fn codegen_commented(&self, generator: &mut dyn Generator, data: &CodegenData) {
+        if let Some(state) = self.named_state() {
+            generator.set_comment(&state.note.borrow());
+        }
+        self.codegen(generator, data);
+    }
+
+    /// Restores state that was stored in the metadata by [`Field::codegen`].
+    fn load_metadata(&self, _metadata: &str) {}
+
+    /// Semantic of hex fields, named fields store it in their metadata.
+    fn semantic(&self) -> Option<Semantic> {
+        None
+    }
+
+    /// State shared by all named fields, `None` for hex fields.
+    fn named_state(&self) -> Option<&NamedState> {
+        None
+    }
+
+    /// Class the field points to, if any.
+    fn target_class(&self) -> Option<ClassId> {
+        None
+    }
+
+    /// Checks if the field, or any of its members, points to the class.
+    fn points_to(&self, class_id: ClassId) -> bool {
+        self.target_class() == Some(class_id)
+    }
+
+    /// Makes pointers to the class `from` point to the class `to` instead.
+    fn retarget(&self, _from: ClassId, _to: ClassId) {}
+
+    /// Calls `f` with every member of the union, in order.
+    fn for_each_member(&self, _f: &mut dyn FnMut(&dyn Field)) {}
+
+    /// Names of the other members of the union the member belongs to,
+    /// `None` if the field doesn't contain the member.
+    fn member_names(&self, _member_id: FieldId) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Renames the field, or its member, with the id. Returns the previous name.
+    fn rename(&self, field_id: FieldId, name: &str) -> Option<String> {
+        let state = self.named_state().filter(|_| self.id() == field_id)?;
+        Some(state.name.replace(name.to_owned()))
+    }
 }
 
 pub struct CodegenData<'a> {
@@ -48,6 +122,8 @@ pub struct EditingState {
     address: usize,
     buf: String,
     should_focus: bool,
+    /// Entered value is frozen after being written.
+    freeze: bool,
 }
 
 impl EditingState {
@@ -56,6 +132,7 @@ impl EditingState {
             address,
             buf,
             should_focus: true,
+            freeze: false,
         }
     }
 }
@@ -67,6 +144,12 @@ pub struct NamedState {
     name: RefCell<String>,
     saved_name: RefCell<String>,
     editing_state: RefCell<Option<EditingState>>,
+    /// `None` means default format of the field.
+    pub format: Cell<Option<DisplayFormat>>,
+    /// Free-text note of the user.
+    pub note: RefCell<String>,
+    /// Pointer was expanded at the top level of the inspector.
+    pub expanded: Cell<bool>,
 }
 
 impl NamedState {
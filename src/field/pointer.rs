@@ -1,57 +1,169 @@
 use super::{
-    create_text_format, display_field_name, display_field_prelude, display_field_value, next_id,
-    CodegenData, Field, FieldId, FieldKind, FieldResponse, NamedState,
+    create_text_format, display_field_name, display_field_prelude, display_field_value,
+    freeze_menu, history_menu, next_id, note_menu, watch_menu, CodegenData, Field, FieldId,
+    FieldKind, FieldResponse, NamedState,
+};
+use crate::{
+    address::parse_address, class::ClassId, context::InspectionContext, generator::Generator, rtti,
+    watch::Link, FID_M,
 };
-use crate::{address::parse_address, context::InspectionContext, generator::Generator, FID_M};
 use eframe::{
     egui::{
         collapsing_header::CollapsingState, popup_below_widget, Id, Label, RichText, Sense,
-        TextFormat, Ui,
+        TextEdit, TextFormat, Ui,
     },
     epaint::{text::LayoutJob, Color32},
 };
 use fastrand::Rng;
-use std::{cell::Cell, mem::transmute};
+use std::{
+    cell::{Cell, RefCell},
+    mem::transmute,
+};
+
+/// Splits metadata of a pointer into the class name and the offset inside of the class,
+/// e.g. `Player+10` points to offset `0x10` of `Player`.
+pub fn split_pointer_metadata(metadata: &str) -> (&str, usize) {
+    match metadata.rsplit_once('+') {
+        Some((class, offset)) => (class, parse_address(offset).unwrap_or_default()),
+        None => (metadata, 0),
+    }
+}
 
 pub struct PointerField {
     id: FieldId,
     state: NamedState,
     class_id: Cell<Option<usize>>,
+    /// One of `Ptr`, `RelPtr32` or `RelPtr64`. Relative pointers store
+    /// a signed offset from the address of the field.
+    kind: FieldKind,
+    /// Offset inside of the class the pointer points to.
+    target_offset: Cell<usize>,
+    offset_buf: RefCell<String>,
 }
 
 impl PointerField {
     pub fn new(name: String) -> Self {
+        Self::new_relative(name, FieldKind::Ptr)
+    }
+
+    pub fn new_relative(name: String, kind: FieldKind) -> Self {
         Self {
             id: next_id(),
             state: NamedState::new(name),
             class_id: None.into(),
+            kind,
+            target_offset: 0.into(),
+            offset_buf: String::new().into(),
         }
     }
 
-    pub fn new_with_class_id(name: String, class_id: usize) -> Self {
-        Self {
-            id: next_id(),
-            state: NamedState::new(name),
-            class_id: Some(class_id).into(),
+    pub fn new_with_class_id(name: String, kind: FieldKind, class_id: usize) -> Self {
+        let field = Self::new_relative(name, kind);
+        field.class_id.set(Some(class_id));
+        field
+    }
+
+    /// Reads the address the pointer points to.
+    fn read_target(&self, ctx: &InspectionContext) -> usize {
+        let paddr = ctx.address + ctx.offset;
+
+        let mut buf = [0; 8];
+        ctx.process.read(paddr, &mut buf[..self.size()]);
+        match self.kind {
+            FieldKind::RelPtr32 => {
+                paddr.wrapping_add_signed(i32::from_ne_bytes(buf[..4].try_into().unwrap()) as isize)
+            }
+            FieldKind::RelPtr64 => paddr.wrapping_add_signed(i64::from_ne_bytes(buf) as isize),
+            _ => usize::from_ne_bytes(buf),
         }
     }
 
-    fn show_header(&self, ui: &mut Ui, ctx: &mut InspectionContext, address: usize) {
+    /// Encodes a new target address, fails if relative offset doesn't fit.
+    fn encode_target(&self, paddr: usize, target: usize) -> Option<Vec<u8>> {
+        let offset = target.wrapping_sub(paddr) as isize;
+
+        Some(match self.kind {
+            FieldKind::RelPtr32 => i32::try_from(offset).ok()?.to_ne_bytes().to_vec(),
+            FieldKind::RelPtr64 => (offset as i64).to_ne_bytes().to_vec(),
+            _ => target.to_ne_bytes().to_vec(),
+        })
+    }
+
+    fn show_popup(&self, ui: &mut Ui, ctx: &InspectionContext) {
+        ui.set_width(120.);
+        ui.vertical_centered_justified(|ui| {
+            for cl in ctx.class_list.classes() {
+                if ui.button(&cl.name).clicked() {
+                    self.class_id.set(Some(cl.id()));
+                    ui.memory_mut(|m| m.close_popup());
+                }
+            }
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("+");
+
+            let buf = &mut *self.offset_buf.borrow_mut();
+            let r = ui.add(TextEdit::singleline(buf).desired_width(64.).hint_text("0"));
+            if r.lost_focus() {
+                self.target_offset
+                    .set(parse_address(buf).unwrap_or_default());
+            }
+            if !r.has_focus() {
+                *buf = format!("{:X}", self.target_offset.get());
+            }
+        })
+        .response
+        .on_hover_text("Offset inside of the class the pointer points to");
+    }
+
+    fn show_header(
+        &self,
+        ui: &mut Ui,
+        ctx: &mut InspectionContext,
+        address: usize,
+    ) -> Option<FieldResponse> {
+        let mut response = None;
         let class = self.class_id.get().and_then(|id| ctx.class_list.by_id(id));
+        let suffix = match self.target_offset.get() {
+            0 => String::new(),
+            offset => format!("+{offset:X}"),
+        };
 
         let (text, exists) = if let Some(cl) = class {
-            (format!("[{}]", cl.name), true)
+            (format!("[{}{suffix}]", cl.name), true)
         } else {
-            (format!("[C{:X}]", address), false)
+            (format!("[C{:X}{suffix}]", address), false)
         };
 
         let mut job = LayoutJob::default();
         display_field_prelude(ui.ctx(), self, ctx, &mut job);
         job.append(" ", 0., TextFormat::default());
 
-        if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+        let r = ui.add(Label::new(job).sense(Sense::click()));
+        if r.clicked() {
             ctx.select(self.id);
         }
+        r.context_menu(|ui| {
+            let class = self
+                .class_id
+                .get()
+                .filter(|id| ctx.class_list.by_id(*id).is_some());
+            if let Some(class_id) = class {
+                if ui.button("Open in new tab").clicked() {
+                    let base = address.wrapping_sub(self.target_offset.get());
+                    response = Some(FieldResponse::OpenTab(class_id, base));
+                    ui.close_menu();
+                }
+                ui.separator();
+            }
+
+            freeze_menu(ui, ctx, &self.state, self.size());
+            history_menu(ui, ctx, &self.state, self.kind());
+            watch_menu(ui, ctx, &self.state, self.kind());
+            note_menu(ui, ctx, &self.state);
+        });
 
         display_field_name(self, ui, ctx, &self.state, Color32::BROWN);
 
@@ -73,14 +185,7 @@ impl PointerField {
                     format!("-> {address:X}")
                 }
             },
-            |new| {
-                if let Some(addr) = parse_address(new) {
-                    ctx.process.write(paddr, &addr.to_ne_bytes());
-                    true
-                } else {
-                    false
-                }
-            },
+            |new| parse_address(new).and_then(|addr| self.encode_target(paddr, addr)),
         );
 
         let mut job = LayoutJob::default();
@@ -104,16 +209,17 @@ impl PointerField {
             ctx.select(self.id);
         }
 
-        popup_below_widget(ui, Id::new(ctx.current_id), &r, eframe::egui::PopupCloseBehavior::CloseOnClick,|ui| {
-            ui.set_width(80.);
-            ui.vertical_centered_justified(|ui| {
-                for cl in ctx.class_list.classes() {
-                    if ui.button(&cl.name).clicked() {
-                        self.class_id.set(Some(cl.id()));
-                    }
-                }
-            });
-        });
+        popup_below_widget(
+            ui,
+            Id::new(ctx.current_id),
+            &r,
+            eframe::egui::PopupCloseBehavior::CloseOnClickOutside,
+            |ui| {
+                self.show_popup(ui, ctx);
+            },
+        );
+
+        response
     }
 
     fn show_body(
@@ -132,6 +238,8 @@ impl PointerField {
         }
 
         let mut response = None;
+        // Interior pointers point into the middle of the class.
+        let base = address.wrapping_sub(self.target_offset.get());
 
         let cid = self.class_id.get()?;
         if let Some(class) = ctx.class_list.by_id(cid) {
@@ -140,29 +248,48 @@ impl PointerField {
             let mut inner_ctx = InspectionContext {
                 class_list: ctx.class_list,
                 parent_id: ctx.current_id,
-                selection: ctx.selection,
+                selection: ctx.selection.take(),
                 current_container: cid,
                 // Will be immideately reassigned.
                 current_id: Id::NULL,
                 process: ctx.process,
                 toasts: ctx.toasts,
+                history: ctx.history,
+                freezes: ctx.freezes,
+                guesses: ctx.guesses,
+                changes: ctx.changes,
+                watches: ctx.watches,
+                dummy: ctx.dummy,
                 level_rng: &rng,
                 offset: 0,
-                address,
+                address: base,
+                path: ctx.path.with(Link {
+                    offset: ctx.offset,
+                    kind: self.kind,
+                    target_offset: self.target_offset.get(),
+                }),
+                modifiers: ctx.modifiers,
             };
 
             #[allow(clippy::single_match)]
-            match class.fields.iter().fold(None, |r, f| {
-                inner_ctx.current_id = Id::new(rng.u64(..));
-                r.or(f.draw(ui, &mut inner_ctx))
-            }) {
+            match inner_ctx.draw_class(ui, class) {
                 Some(other) => response = Some(other),
                 None => {}
             }
 
             ctx.selection = inner_ctx.selection;
         } else {
-            response = Some(FieldResponse::NewClass(format!("C{:X}", address), cid));
+            let name = rtti::resolve(ctx.process, base).map(|ti| ti.class_name());
+
+            // Reuse already existing class if type of the object is known.
+            if let Some(existing) = name.as_deref().and_then(|n| ctx.class_list.by_name(n)) {
+                self.class_id.set(Some(existing.id()));
+            } else {
+                response = Some(FieldResponse::NewClass(
+                    name.unwrap_or_else(|| format!("C{:X}", base)),
+                    cid,
+                ));
+            }
         }
 
         response
@@ -178,37 +305,45 @@ impl Field for PointerField {
         // TODO(ItsEthra): When inspecting 32-bit processes
         // size of the pointer would be `4`. But I am not sure
         // if the rest of this app isn't break in this case lol.
-        8
+        self.kind.size()
     }
 
     fn name(&self) -> Option<String> {
         Some(self.state.name.borrow().clone())
     }
 
+    fn named_state(&self) -> Option<&NamedState> {
+        Some(&self.state)
+    }
+
     fn kind(&self) -> FieldKind {
-        FieldKind::Ptr
+        self.kind
     }
 
     fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
         let mut response = None;
 
         // TODO(ItsEthra): Again, pointer size differs in 32-bit processes.
-        let mut buf = [0; 8];
-        ctx.process.read(ctx.address + ctx.offset, &mut buf);
-        let address = usize::from_ne_bytes(buf);
+        let address = self.read_target(ctx);
 
         if self.class_id.get().is_none() {
             self.class_id.set(Some(fastrand::usize(..)));
         }
 
-        let state = CollapsingState::load_with_default_open(ui.ctx(), ctx.current_id, false);
-        let body = state
+        // Only pointers at the top level remember being expanded,
+        // nested ones may point back to the class.
+        let top_level = ctx.path.links.is_empty();
+        let default_open = top_level && self.state.expanded.get();
+        let state = CollapsingState::load_with_default_open(ui.ctx(), ctx.current_id, default_open);
+        if top_level {
+            self.state.expanded.set(state.is_open());
+        }
+        let (_, header, body) = state
             .show_header(ui, |ui| self.show_header(ui, ctx, address))
-            .body(|ui| self.show_body(ui, ctx, address))
-            .2;
+            .body(|ui| self.show_body(ui, ctx, address));
         let body = body.and_then(|inner| inner.inner);
 
-        if let Some(new) = body {
+        if let Some(new) = body.or(header.inner) {
             response = Some(new);
         }
 
@@ -217,13 +352,33 @@ impl Field for PointerField {
     }
 
     fn codegen(&self, generator: &mut dyn Generator, data: &CodegenData) {
+        let class = data
+            .classes
+            .iter()
+            .find(|c| Some(c.id()) == self.class_id.get())
+            .map(|c| match self.target_offset.get() {
+                0 => c.name.clone(),
+                offset => format!("{}+{offset:X}", c.name),
+            });
+
         generator.add_field(
             self.state.name.borrow().as_str(),
-            FieldKind::Ptr,
-            data.classes
-                .iter()
-                .find(|c| c.id() == self.class_id.get().unwrap())
-                .map(|c| c.name.as_ref()),
+            self.kind,
+            class.as_deref(),
         );
     }
+
+    fn load_metadata(&self, metadata: &str) {
+        self.target_offset.set(split_pointer_metadata(metadata).1);
+    }
+
+    fn target_class(&self) -> Option<ClassId> {
+        self.class_id.get()
+    }
+
+    fn retarget(&self, from: ClassId, to: ClassId) {
+        if self.class_id.get() == Some(from) {
+            self.class_id.set(Some(to));
+        }
+    }
 }
@@ -0,0 +1,293 @@
+//! Semantic display types decode well-known encodings of integer and hex fields.
+//! Semantic of named fields is stored in their metadata, e.g. `fixed:65536`.
+use crate::field::FieldKind;
+use eframe::{
+    egui::{Id, Response, Sense, TextEdit, Ui},
+    epaint::{vec2, Color32},
+};
+use std::cell::Cell;
+
+/// Seconds between `1601-01-01` and `1970-01-01`.
+const FILETIME_EPOCH_DIFF: i64 = 11_644_473_600;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Semantic {
+    /// Seconds since `1970-01-01`.
+    UnixTime,
+    /// 100-nanosecond intervals since `1601-01-01`.
+    FileTime,
+    Rgba,
+    Argb,
+    /// Reads 16 bytes starting at the field.
+    Guid,
+    /// Address in network byte order. 8-byte fields are treated
+    /// as the beginning of `sockaddr_in`, i.e. family, port and address.
+    Ipv4,
+    /// Fixed-point number, raw value is divided by the scale.
+    Fixed(u32),
+}
+
+impl Semantic {
+    pub const FIXED_SCALES: &[(u32, &'static str)] = &[
+        (1 << 8, "8.8"),
+        (1 << 12, "20.12"),
+        (1 << 16, "16.16"),
+        (10, "1/10"),
+        (100, "1/100"),
+        (1000, "1/1000"),
+    ];
+
+    /// Variants that can be displayed by a field of `size` bytes.
+    /// `spanning` allows variants that read past the end of the field.
+    pub fn variants(size: usize, spanning: bool) -> Vec<Self> {
+        [
+            Self::UnixTime,
+            Self::FileTime,
+            Self::Rgba,
+            Self::Argb,
+            Self::Guid,
+            Self::Ipv4,
+        ]
+        .into_iter()
+        .filter(|s| s.supports(size) && (spanning || s.read_size(size) == size))
+        .collect()
+    }
+
+    pub fn supports(&self, size: usize) -> bool {
+        match self {
+            Self::UnixTime | Self::Ipv4 => size == 4 || size == 8,
+            Self::FileTime | Self::Guid => size == 8,
+            Self::Rgba | Self::Argb => size == 4,
+            Self::Fixed(_) => size > 1,
+        }
+    }
+
+    /// Amount of bytes that must be read to display a field of `size` bytes.
+    pub fn read_size(&self, size: usize) -> usize {
+        match self {
+            Self::Guid => 16,
+            _ => size,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::UnixTime => "Unix time".into(),
+            Self::FileTime => "FILETIME".into(),
+            Self::Rgba => "RGBA".into(),
+            Self::Argb => "ARGB".into(),
+            Self::Guid => "GUID".into(),
+            Self::Ipv4 => "IPv4".into(),
+            Self::Fixed(scale) => format!("Fixed(1/{scale})"),
+        }
+    }
+
+    pub fn to_metadata(self) -> String {
+        match self {
+            Self::UnixTime => "unix_time".into(),
+            Self::FileTime => "filetime".into(),
+            Self::Rgba => "rgba".into(),
+            Self::Argb => "argb".into(),
+            Self::Guid => "guid".into(),
+            Self::Ipv4 => "ipv4".into(),
+            Self::Fixed(scale) => format!("fixed:{scale}"),
+        }
+    }
+
+    pub fn from_metadata(metadata: &str) -> Option<Self> {
+        Some(match metadata {
+            "unix_time" => Self::UnixTime,
+            "filetime" => Self::FileTime,
+            "rgba" => Self::Rgba,
+            "argb" => Self::Argb,
+            "guid" => Self::Guid,
+            "ipv4" => Self::Ipv4,
+            other => Self::Fixed(
+                other
+                    .strip_prefix("fixed:")?
+                    .parse()
+                    .ok()
+                    .filter(|s| *s != 0)?,
+            ),
+        })
+    }
+
+    /// Semantic stored in the metadata of a field of `kind`.
+    pub fn from_field(kind: FieldKind, metadata: Option<&str>) -> Option<Self> {
+        use FieldKind::*;
+
+        if matches!(kind, I8 | I16 | I32 | I64 | U8 | U16 | U32 | U64) {
+            Self::from_metadata(metadata?).filter(|s| s.supports(kind.size()))
+        } else {
+            None
+        }
+    }
+
+    /// C++ type of a field of `kind` with this semantic.
+    pub fn cpp_type(&self, kind: FieldKind) -> Option<&'static str> {
+        match (self, kind.size()) {
+            (Self::UnixTime, 8) => Some("std::time_t"),
+            _ => None,
+        }
+    }
+
+    /// Rust type of a field of `kind` with this semantic.
+    pub fn rust_type(&self, kind: FieldKind) -> Option<&'static str> {
+        match (self, kind.size()) {
+            (Self::Rgba | Self::Argb | Self::Ipv4, 4) => Some("[u8; 4]"),
+            _ => None,
+        }
+    }
+
+    /// Formats `bytes`, which must be at least [`Semantic::read_size`] long.
+    pub fn format(&self, bytes: &[u8], signed: bool) -> String {
+        match self {
+            Self::UnixTime => format_unix_time(read_int(bytes, true)),
+            Self::FileTime => {
+                let ft = read_int(bytes, false);
+                format_unix_time(ft / 10_000_000 - FILETIME_EPOCH_DIFF)
+            }
+            Self::Rgba | Self::Argb => {
+                let [r, g, b, a] = self.rgba(bytes);
+                format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+            }
+            Self::Guid => format!(
+                "{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{}}}",
+                u32::from_le_bytes(bytes[..4].try_into().unwrap()),
+                u16::from_le_bytes(bytes[4..6].try_into().unwrap()),
+                u16::from_le_bytes(bytes[6..8].try_into().unwrap()),
+                bytes[8],
+                bytes[9],
+                bytes[10..16]
+                    .iter()
+                    .map(|b| format!("{b:02X}"))
+                    .collect::<String>()
+            ),
+            Self::Ipv4 if bytes.len() == 8 => {
+                let port = u16::from_be_bytes(bytes[2..4].try_into().unwrap());
+                let [a, b, c, d] = bytes[4..8].try_into().unwrap();
+                format!("{a}.{b}.{c}.{d}:{port}")
+            }
+            Self::Ipv4 => format!("{}.{}.{}.{}", bytes[0], bytes[1], bytes[2], bytes[3]),
+            Self::Fixed(scale) => {
+                let raw = read_int(bytes, signed);
+                format!("{}", raw as f64 / *scale as f64)
+            }
+        }
+    }
+
+    /// Color of the swatch, if this semantic is a color.
+    pub fn color(&self, bytes: &[u8]) -> Option<Color32> {
+        match self {
+            Self::Rgba | Self::Argb => {
+                let [r, g, b, a] = self.rgba(bytes);
+                Some(Color32::from_rgba_unmultiplied(r, g, b, a))
+            }
+            _ => None,
+        }
+    }
+
+    fn rgba(&self, bytes: &[u8]) -> [u8; 4] {
+        let [x, y, z, w] = bytes[..4].try_into().unwrap();
+        if *self == Self::Argb {
+            [y, z, w, x]
+        } else {
+            [x, y, z, w]
+        }
+    }
+}
+
+/// Draws a color swatch next to the value.
+pub fn color_swatch(ui: &mut Ui, color: Color32) -> Response {
+    let h = ui.text_style_height(&eframe::egui::TextStyle::Monospace);
+    let (rect, r) = ui.allocate_exact_size(vec2(h * 1.5, h), Sense::hover());
+    ui.painter().rect_filled(rect, 2., color);
+    r
+}
+
+/// Context menu to pick the semantic of the field of `size` bytes.
+pub fn semantic_menu(ui: &mut Ui, semantic: &Cell<Option<Semantic>>, size: usize, spanning: bool) {
+    ui.menu_button("Display as", |ui| {
+        if ui
+            .selectable_label(semantic.get().is_none(), "Default")
+            .clicked()
+        {
+            semantic.set(None);
+            ui.close_menu();
+        }
+
+        for var in Semantic::variants(size, spanning) {
+            if ui
+                .selectable_label(semantic.get() == Some(var), var.label())
+                .clicked()
+            {
+                semantic.set(Some(var));
+                ui.close_menu();
+            }
+        }
+
+        if size > 1 {
+            ui.menu_button("Fixed-point", |ui| {
+                for (scale, label) in Semantic::FIXED_SCALES {
+                    if ui
+                        .selectable_label(semantic.get() == Some(Semantic::Fixed(*scale)), *label)
+                        .clicked()
+                    {
+                        semantic.set(Some(Semantic::Fixed(*scale)));
+                        ui.close_menu();
+                    }
+                }
+
+                let id = Id::new("_fixed_point_scale");
+                let mut buf = ui.data_mut(|d| d.get_temp::<String>(id).unwrap_or_default());
+                let r = ui.add(
+                    TextEdit::singleline(&mut buf)
+                        .desired_width(80.)
+                        .hint_text("Scale"),
+                );
+                if r.lost_focus() {
+                    if let Some(scale) = buf.parse::<u32>().ok().filter(|s| *s != 0) {
+                        semantic.set(Some(Semantic::Fixed(scale)));
+                        ui.close_menu();
+                    }
+                }
+                ui.data_mut(|d| d.insert_temp(id, buf));
+            });
+        }
+    });
+}
+
+fn read_int(bytes: &[u8], signed: bool) -> i64 {
+    match bytes.len() {
+        1 if signed => bytes[0] as i8 as i64,
+        2 if signed => i16::from_le_bytes(bytes[..2].try_into().unwrap()) as i64,
+        4 if signed => i32::from_le_bytes(bytes[..4].try_into().unwrap()) as i64,
+        1 => bytes[0] as i64,
+        2 => u16::from_le_bytes(bytes[..2].try_into().unwrap()) as i64,
+        4 => u32::from_le_bytes(bytes[..4].try_into().unwrap()) as i64,
+        _ => i64::from_le_bytes(bytes[..8].try_into().unwrap()),
+    }
+}
+
+fn format_unix_time(secs: i64) -> String {
+    let (days, rem) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+
+    // Converts days since the epoch into a civil date,
+    // see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
@@ -6,8 +6,8 @@ use eframe::{
 use crate::FID_M;
 
 use super::{
-    display_field_name, display_field_prelude, display_field_value, next_id, Field, FieldId,
-    FieldKind, NamedState,
+    display_field_name, display_field_prelude, display_field_value, freeze_menu, history_menu,
+    next_id, note_menu, watch_menu, Field, FieldId, FieldKind, NamedState,
 };
 
 pub struct StringPointerField {
@@ -33,6 +33,10 @@ impl Field for StringPointerField {
         Some(self.state.name.borrow().clone())
     }
 
+    fn named_state(&self) -> Option<&NamedState> {
+        Some(&self.state)
+    }
+
     fn size(&self) -> usize {
         // TODO: The size of the pointer would be 4 bytes on x86
         8
@@ -58,9 +62,16 @@ impl Field for StringPointerField {
         ui.horizontal(|ui| {
             let mut job = LayoutJob::default();
             display_field_prelude(ui.ctx(), self, ctx, &mut job);
-            if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+            let r = ui.add(Label::new(job).sense(Sense::click()));
+            if r.clicked() {
                 ctx.select(self.id);
             }
+            r.context_menu(|ui| {
+                freeze_menu(ui, ctx, &self.state, 8);
+                history_menu(ui, ctx, &self.state, self.kind());
+                watch_menu(ui, ctx, &self.state, self.kind());
+                note_menu(ui, ctx, &self.state);
+            });
             display_field_name(self, ui, ctx, &self.state, Color32::LIGHT_RED);
             if ctx.process.can_read(address) {
                 display_field_value(
@@ -82,7 +93,7 @@ impl Field for StringPointerField {
                             format!("-> {str:?}")
                         }
                     },
-                    |_| false,
+                    |_| None,
                 )
             } else {
                 ui.add_space(2.);
@@ -0,0 +1,307 @@
+use super::{
+    create_text_format, display_field_name, display_field_prelude, next_id, CodegenData, Field,
+    FieldId, FieldKind, FieldResponse, NamedState,
+};
+use crate::{
+    address::parse_address, class::ClassId, context::InspectionContext, generator::Generator,
+};
+use eframe::{
+    egui::{Label, Sense, TextEdit, Ui},
+    epaint::{text::LayoutJob, Color32},
+};
+use std::cell::{Cell, RefCell};
+
+/// Kinds that are allowed to be members of a union.
+const MEMBER_KINDS: &[FieldKind] = &[
+    FieldKind::Bool,
+    FieldKind::U8,
+    FieldKind::U16,
+    FieldKind::U32,
+    FieldKind::U64,
+    FieldKind::I8,
+    FieldKind::I16,
+    FieldKind::I32,
+    FieldKind::I64,
+    FieldKind::F32,
+    FieldKind::F64,
+    FieldKind::Ptr,
+    FieldKind::RelPtr32,
+    FieldKind::RelPtr64,
+    FieldKind::StrPtr,
+    FieldKind::FnPtr,
+];
+
+pub struct UnionField {
+    id: FieldId,
+    state: NamedState,
+    members: RefCell<Vec<Box<dyn Field>>>,
+    /// Offset of the tag field inside of the containing class.
+    /// Value of the tag is used as an index of the displayed member.
+    tag_offset: Cell<Option<usize>>,
+    tag_buf: RefCell<String>,
+}
+
+impl UnionField {
+    pub fn new(name: String) -> Self {
+        Self::with_members(
+            name,
+            vec![
+                FieldKind::U64.into_field(None),
+                FieldKind::F64.into_field(None),
+            ],
+            None,
+        )
+    }
+
+    pub fn with_members(
+        name: String,
+        members: Vec<Box<dyn Field>>,
+        tag_offset: Option<usize>,
+    ) -> Self {
+        Self {
+            id: next_id(),
+            state: NamedState::new(name),
+            members: members.into(),
+            tag_offset: tag_offset.into(),
+            tag_buf: tag_offset
+                .map(|o| format!("{o:X}"))
+                .unwrap_or_default()
+                .into(),
+        }
+    }
+
+    /// Reads the value of the tag field, if there is one.
+    fn read_tag(&self, ctx: &InspectionContext) -> Option<usize> {
+        let tag_offset = self.tag_offset.get()?;
+        let class = ctx.class_list.by_id(ctx.current_container)?;
+
+        let mut offset = 0;
+        let tag_size = class.fields.iter().find_map(|f| {
+            let current = offset;
+            offset += f.size();
+            (current == tag_offset).then(|| f.size())
+        })?;
+
+        // Tag offset is relative to the first own field of the class.
+        let base_size = ctx.class_list.base_size(ctx.current_container);
+
+        let mut buf = [0; 8];
+        ctx.process.read(
+            ctx.address + base_size + tag_offset,
+            &mut buf[..tag_size.min(8)],
+        );
+        Some(u64::from_le_bytes(buf) as usize)
+    }
+
+    fn show_menu(&self, ui: &mut Ui) {
+        ui.menu_button("Add member", |ui| {
+            for kind in MEMBER_KINDS {
+                if ui.button(format!("{kind:?}")).clicked() {
+                    let mut members = self.members.borrow_mut();
+                    let member = new_member(&members, *kind);
+                    members.push(member);
+                    ui.close_menu();
+                }
+            }
+        });
+
+        ui.add_enabled_ui(self.members.borrow().len() > 1, |ui| {
+            ui.menu_button("Remove member", |ui| {
+                let mut remove = None;
+                for (i, member) in self.members.borrow().iter().enumerate() {
+                    let label = member
+                        .name()
+                        .unwrap_or_else(|| format!("{:?}", member.kind()));
+                    if ui.button(label).clicked() {
+                        remove = Some(i);
+                    }
+                }
+
+                if let Some(i) = remove {
+                    self.members.borrow_mut().remove(i);
+                    ui.close_menu();
+                }
+            });
+        });
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Tag offset");
+
+            let buf = &mut *self.tag_buf.borrow_mut();
+            let r = ui.add(
+                TextEdit::singleline(buf)
+                    .desired_width(64.)
+                    .hint_text("None"),
+            );
+            if r.lost_focus() {
+                self.tag_offset.set(parse_address(buf));
+            }
+        })
+        .response
+        .on_hover_text("Offset of the field whose value selects the displayed member");
+    }
+}
+
+impl Field for UnionField {
+    fn id(&self) -> FieldId {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.state.name.borrow().clone())
+    }
+
+    fn named_state(&self) -> Option<&NamedState> {
+        Some(&self.state)
+    }
+
+    fn size(&self) -> usize {
+        self.members
+            .borrow()
+            .iter()
+            .map(|m| m.size())
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn kind(&self) -> FieldKind {
+        FieldKind::Union
+    }
+
+    fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
+        let (start, parent_id) = (ctx.offset, ctx.current_id);
+        let tag = self.read_tag(ctx);
+
+        ui.horizontal(|ui| {
+            let mut job = LayoutJob::default();
+            display_field_prelude(ui.ctx(), self, ctx, &mut job);
+
+            if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+                ctx.select(self.id);
+            }
+
+            display_field_name(self, ui, ctx, &self.state, Color32::KHAKI);
+
+            let mut job = LayoutJob::default();
+            job.append(
+                &match tag {
+                    Some(tag) => format!("union [{}] (tag = {tag})", self.members.borrow().len()),
+                    None => format!("union [{}]", self.members.borrow().len()),
+                },
+                4.,
+                create_text_format(ctx.is_selected(self.id), Color32::GRAY),
+            );
+
+            let r = ui.add(Label::new(job).sense(Sense::click()));
+            if r.clicked() {
+                ctx.select(self.id);
+            }
+            r.context_menu(|ui| self.show_menu(ui));
+        });
+
+        let mut response = None;
+        ui.indent(ctx.current_id, |ui| {
+            for (i, member) in self.members.borrow().iter().enumerate() {
+                if tag.is_some_and(|t| t != i) {
+                    continue;
+                }
+
+                ctx.offset = start;
+                ctx.current_id = parent_id.with(i);
+                response = response.take().or(member.draw(ui, ctx));
+            }
+        });
+
+        // Members can't be edited with the tool bar, so the whole union is selected instead.
+        if let Some(selection) = ctx.selection.as_mut() {
+            if self
+                .members
+                .borrow()
+                .iter()
+                .any(|m| m.id() == selection.field_id)
+            {
+                selection.field_id = self.id;
+            }
+        }
+
+        ctx.current_id = parent_id;
+        ctx.offset = start + self.size();
+        response
+    }
+
+    fn codegen(&self, generator: &mut dyn Generator, data: &CodegenData) {
+        generator.begin_union(self.state.name.borrow().as_str(), self.tag_offset.get());
+        for member in self.members.borrow().iter() {
+            member.codegen_commented(generator, data);
+        }
+        generator.end_union(self.size());
+    }
+
+    fn alignment(&self) -> usize {
+        self.members
+            .borrow()
+            .iter()
+            .map(|m| m.alignment())
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn points_to(&self, class_id: ClassId) -> bool {
+        self.members.borrow().iter().any(|m| m.points_to(class_id))
+    }
+
+    fn retarget(&self, from: ClassId, to: ClassId) {
+        self.members
+            .borrow()
+            .iter()
+            .for_each(|m| m.retarget(from, to));
+    }
+
+    fn for_each_member(&self, f: &mut dyn FnMut(&dyn Field)) {
+        self.members.borrow().iter().for_each(|m| f(m.as_ref()));
+    }
+
+    fn member_names(&self, member_id: FieldId) -> Option<Vec<String>> {
+        let members = self.members.borrow();
+        if members.iter().any(|m| m.id() == member_id) {
+            return Some(
+                members
+                    .iter()
+                    .filter(|m| m.id() != member_id)
+                    .filter_map(|m| m.name())
+                    .collect(),
+            );
+        }
+
+        members.iter().find_map(|m| m.member_names(member_id))
+    }
+
+    fn rename(&self, field_id: FieldId, name: &str) -> Option<String> {
+        if self.id == field_id {
+            return Some(self.state.name.replace(name.to_owned()));
+        }
+
+        self.members
+            .borrow()
+            .iter()
+            .find_map(|m| m.rename(field_id, name))
+    }
+}
+
+/// Creates a member with the default name of the kind,
+/// suffixed with the position of the member if the name is taken.
+fn new_member(members: &[Box<dyn Field>], kind: FieldKind) -> Box<dyn Field> {
+    let member = kind.into_field(None);
+    let name = member.name().unwrap_or_default();
+    if !members.iter().any(|m| m.name().as_ref() == Some(&name)) {
+        return member;
+    }
+
+    let name = (members.len()..)
+        .map(|i| format!("{name}_{i}"))
+        .find(|n| !members.iter().any(|m| m.name().as_ref() == Some(n)))
+        .unwrap();
+    kind.into_field(Some(name))
+}
@@ -1,8 +1,11 @@
-use super::{create_text_format, EditingState, Field, HexField, NamedState};
-use crate::{app::is_valid_ident, context::InspectionContext, FID_M};
+use super::{create_text_format, EditingState, Field, FieldKind, HexField, NamedState};
+use crate::{
+    app::is_valid_ident, changes::Plot, context::InspectionContext, freeze::Freeze,
+    history::Command, watch::Watch, FID_M,
+};
 use eframe::{
-    egui::{Context, FontSelection, Key, Label, Modifiers, Sense, TextEdit, Ui},
-    epaint::{text::LayoutJob, Color32, Stroke},
+    egui::{Button, Context, Event, FontSelection, Key, Label, Response, Sense, TextEdit, Ui},
+    epaint::{pos2, text::LayoutJob, vec2, Color32, Shape, Stroke, Vec2},
 };
 use std::fmt::Display;
 
@@ -14,25 +17,38 @@ pub fn display_field_prelude(
 ) {
     job.append(&format!("{:04X}", ctx.offset), 0., {
         let mut tf = create_text_format(ctx.is_selected(field.id()), Color32::KHAKI);
-        // Highlight unaligned fields
-        if ctx.offset % 8 != 0 {
+        // Highlight unaligned fields, hex fields are expected at multiples of 8.
+        let alignment = match field.name() {
+            Some(_) => field.alignment(),
+            None => 8,
+        };
+        if ctx.offset % alignment != 0 {
             tf.underline = Stroke::new(1., Color32::RED);
         }
 
-        if egui_ctx.input(|i| i.key_pressed(Key::C))
-            && egui_ctx.input(|i| i.modifiers.matches(Modifiers::CTRL))
-            && ctx.is_selected(field.id())
-        {
-            egui_ctx.output_mut(|o| o.copied_text = format!("{:X}", ctx.address + ctx.offset));
-        }
+        // Copy shortcut usually arrives as an event instead of a key press.
+        let (copy, shift) = egui_ctx.input(|i| {
+            (
+                i.events.contains(&Event::Copy) || (i.key_pressed(Key::C) && i.modifiers.command),
+                i.modifiers.shift,
+            )
+        });
+        if copy && ctx.is_selected(field.id()) && !egui_ctx.wants_keyboard_input() {
+            let text = if shift {
+                let mut buf = [0; 8];
+                ctx.process.read(ctx.address + ctx.offset, &mut buf[..]);
+                format!("{:X}", usize::from_ne_bytes(buf))
+            } else {
+                format!("{:X}", ctx.address + ctx.offset)
+            };
 
-        if egui_ctx.input(|i| i.key_pressed(Key::C))
-            && egui_ctx.input(|i| i.modifiers.matches(Modifiers::CTRL | Modifiers::SHIFT))
-            && ctx.is_selected(field.id())
-        {
-            let mut buf = [0; 8];
-            ctx.process.read(ctx.address + ctx.offset, &mut buf[..]);
-            egui_ctx.output_mut(|o| o.copied_text = format!("{:X}", usize::from_ne_bytes(buf)));
+            // Every selected field is copied on its own line.
+            egui_ctx.output_mut(|o| {
+                if !o.copied_text.is_empty() {
+                    o.copied_text.push('\n');
+                }
+                o.copied_text += &text;
+            });
         }
 
         tf
@@ -54,13 +70,16 @@ pub fn display_field_value<T: Display>(
     // the value returned would be used as initial value for
     // text edit box.
     mut displayed_value: impl FnMut(bool) -> T,
-    write_new_value: impl FnOnce(&str) -> bool,
+    // Returns bytes that are written at the address of the field,
+    // `None` means that the new value is invalid.
+    write_new_value: impl FnOnce(&str) -> Option<Vec<u8>>,
 ) {
     let editing_value = &mut *state.editing_state.borrow_mut();
     if let Some(EditingState {
         address,
         should_focus,
         buf,
+        freeze,
     }) = editing_value
     {
         if *address == ctx.address + ctx.offset {
@@ -83,11 +102,23 @@ pub fn display_field_value<T: Display>(
             if r.clicked_elsewhere() {
                 *editing_value = None;
             } else if r.lost_focus() {
-                if !write_new_value(buf) {
+                if let Some(bytes) = write_new_value(buf) {
+                    let (pid, address) = (ctx.process.id(), ctx.address + ctx.offset);
+                    let written = ctx.write(address, &bytes);
+
+                    // Otherwise frozen value would immediately overwrite the new one.
+                    if written && (*freeze || ctx.freezes.is_frozen(pid, address)) {
+                        ctx.freezes.freeze(Freeze {
+                            pid,
+                            address,
+                            bytes,
+                            label: freeze_label(ctx, state),
+                        });
+                    }
+                    *editing_value = None;
+                } else {
                     ctx.toasts.error("Invalid value");
                     *should_focus = true;
-                } else {
-                    *editing_value = None;
                 }
             }
 
@@ -95,15 +126,46 @@ pub fn display_field_value<T: Display>(
         }
     }
 
+    let address = ctx.address + ctx.offset;
+    let mut bytes = vec![0; field.size()];
+    ctx.process.read(address, &mut bytes);
+    let heat = ctx
+        .changes
+        .observe(address, &bytes)
+        .into_iter()
+        .fold(0f32, f32::max);
+
     let mut job = LayoutJob::default();
-    job.append(
-        &displayed_value(false).to_string(),
-        0.,
-        create_text_format(ctx.is_selected(field.id()), color),
-    );
+    job.append(&displayed_value(false).to_string(), 0., {
+        let mut tf = create_text_format(ctx.is_selected(field.id()), color);
+        if heat > 0. {
+            tf.background = change_highlight(heat);
+        }
+        tf
+    });
+    if ctx
+        .freezes
+        .is_frozen(ctx.process.id(), ctx.address + ctx.offset)
+    {
+        job.append(
+            "[frozen]",
+            6.,
+            create_text_format(ctx.is_selected(field.id()), Color32::LIGHT_BLUE),
+        );
+    }
+
+    let mut r = ui.add(Label::new(job).sense(Sense::click()));
+    if r.hovered() {
+        let history = ctx.changes.history(address, field.kind());
+        if history.len() > 1 {
+            r = r.on_hover_ui(|ui| _ = plot_values(ui, &history, vec2(160., 40.)));
+        }
+    }
 
-    let r = ui.add(Label::new(job).sense(Sense::click()));
-    if r.secondary_clicked() {
+    if r.secondary_clicked() && ctx.process.is_read_only() {
+        ctx.toasts
+            .warning("Read-only mode is enabled, values can't be edited");
+    } else if r.secondary_clicked() {
         *editing_value = Some(EditingState::new(
             ctx.address + ctx.offset,
             displayed_value(true).to_string(),
@@ -113,6 +175,128 @@ pub fn display_field_value<T: Display>(
     }
 }
 
+/// Context menu entries to freeze the value of the named field of `size` bytes.
+pub fn freeze_menu(ui: &mut Ui, ctx: &mut InspectionContext, state: &NamedState, size: usize) {
+    let (pid, address) = (ctx.process.id(), ctx.address + ctx.offset);
+
+    if ctx.freezes.is_frozen(pid, address) {
+        if ui.button("Unfreeze").clicked() {
+            ctx.freezes.unfreeze(pid, address);
+            ui.close_menu();
+        }
+        return;
+    }
+
+    if ctx.process.is_read_only() {
+        ui.add_enabled(false, Button::new("Freeze"))
+            .on_disabled_hover_text("Read-only mode is enabled");
+        return;
+    }
+
+    if ui.button("Freeze").clicked() {
+        let mut bytes = vec![0; size];
+        ctx.process.read(address, &mut bytes);
+        ctx.freezes.freeze(Freeze {
+            pid,
+            address,
+            bytes,
+            label: freeze_label(ctx, state),
+        });
+        ui.close_menu();
+    }
+
+    if ui.button("Freeze at value").clicked() {
+        *state.editing_state.borrow_mut() = Some(EditingState {
+            freeze: true,
+            ..EditingState::new(address, String::new())
+        });
+        ui.close_menu();
+    }
+}
+
+/// Context menu entry that plots the history of values of the named field.
+pub fn history_menu(ui: &mut Ui, ctx: &InspectionContext, state: &NamedState, kind: FieldKind) {
+    if ui.button("Value history").clicked() {
+        ctx.changes.plot(Plot {
+            address: ctx.address + ctx.offset,
+            kind,
+            label: freeze_label(ctx, state),
+        });
+        ui.close_menu();
+    }
+}
+
+/// Context menu entry that pins the named field to the watch list.
+pub fn watch_menu(ui: &mut Ui, ctx: &InspectionContext, state: &NamedState, kind: FieldKind) {
+    if ui.button("Add to watch list").clicked() {
+        ctx.watches.add(Watch {
+            path: ctx.path.clone(),
+            offset: ctx.offset,
+            kind,
+            label: freeze_label(ctx, state),
+        });
+        ui.close_menu();
+    }
+}
+
+/// Context menu entry to edit the note of the named field.
+pub fn note_menu(ui: &mut Ui, ctx: &mut InspectionContext, state: &NamedState) {
+    ui.menu_button("Note", |ui| {
+        let r = TextEdit::multiline(&mut *state.note.borrow_mut())
+            .hint_text("Note")
+            .show(ui)
+            .response;
+        if r.changed() {
+            *ctx.dummy = false;
+        }
+    })
+    .response
+    .on_hover_text("Generated code includes it as a comment");
+}
+
+/// Background of bytes that changed recently, `heat` fades from `1` to `0`.
+pub fn change_highlight(heat: f32) -> Color32 {
+    Color32::from_rgba_unmultiplied(200, 60, 60, (heat * 160.) as u8)
+}
+
+/// Draws values as a line, the oldest one on the left.
+/// Values are paired with seconds passed since they were read.
+pub fn plot_values(ui: &mut Ui, values: &[(f32, f64)], size: Vec2) -> Response {
+    let (rect, response) = ui.allocate_exact_size(size, Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0., Color32::from_gray(20));
+
+    let Some(&(span, _)) = values.first() else {
+        return response;
+    };
+    let (min, max) = values
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(min, max), (_, v)| {
+            (min.min(*v), max.max(*v))
+        });
+    let range = (max - min).max(f64::EPSILON);
+
+    let points = values
+        .iter()
+        .map(|(age, v)| {
+            pos2(
+                rect.right() - rect.width() * age / span.max(f32::EPSILON),
+                rect.bottom() - rect.height() * ((v - min) / range) as f32,
+            )
+        })
+        .collect();
+    painter.add(Shape::line(points, Stroke::new(1.5, Color32::LIGHT_BLUE)));
+
+    response
+}
+
+fn freeze_label(ctx: &InspectionContext, state: &NamedState) -> String {
+    match ctx.class_list.by_id(ctx.current_container) {
+        Some(class) => format!("{}.{}", class.name, state.name.borrow()),
+        None => state.name.borrow().clone(),
+    }
+}
+
 pub fn display_field_name(
     field: &dyn Field,
     ui: &mut Ui,
@@ -157,8 +341,20 @@ pub fn display_field_name(
             if !is_valid_ident(name) {
                 ctx.toasts.error("Not a valid field name");
                 state.focused_id.set(Some(ctx.current_id));
+            } else if sibling_names(ctx, field).contains(name) {
+                ctx.toasts.error("Field with this name already exists");
+                state.focused_id.set(Some(ctx.current_id));
             } else {
                 state.renaming_id.set(None);
+
+                let saved = std::mem::take(&mut *state.saved_name.borrow_mut());
+                if saved != *name {
+                    ctx.history.push(Command::RenameField {
+                        class_id: ctx.current_container,
+                        field_id: field.id(),
+                        name: saved,
+                    });
+                }
             }
         }
     } else {
@@ -169,7 +365,11 @@ pub fn display_field_name(
             create_text_format(ctx.is_selected(field.id()), color),
         );
 
-        let r = ui.add(Label::new(job).sense(Sense::click()));
+        let mut r = ui.add(Label::new(job).sense(Sense::click()));
+        let note = state.note.borrow();
+        if !note.is_empty() {
+            r = r.on_hover_text(note.as_str());
+        }
         if r.secondary_clicked() {
             *state.saved_name.borrow_mut() = state.name.borrow().clone();
             state.renaming_id.set(Some(ctx.current_id));
@@ -180,6 +380,27 @@ pub fn display_field_name(
     }
 }
 
+/// Names of the other fields of the class, or of the other members if the field is in a union.
+fn sibling_names(ctx: &InspectionContext, field: &dyn Field) -> Vec<String> {
+    let Some(class) = ctx.class_list.by_id(ctx.current_container) else {
+        return vec![];
+    };
+
+    let id = field.id();
+    class
+        .fields
+        .iter()
+        .find_map(|f| f.member_names(id))
+        .unwrap_or_else(|| {
+            class
+                .fields
+                .iter()
+                .filter(|f| f.id() != id)
+                .filter_map(|f| f.name())
+                .collect()
+        })
+}
+
 pub fn allocate_padding(mut n: usize) -> Vec<Box<dyn Field>> {
     let mut fields = vec![];
 
@@ -0,0 +1,102 @@
+//! This module keeps frozen values written into the process memory.
+//! Values are rewritten by a background thread until they are unfrozen.
+use crate::process::YProcess;
+use parking_lot::{Mutex, RwLock};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+pub const DEFAULT_INTERVAL_MS: u64 = 50;
+
+#[derive(Debug, Clone)]
+pub struct Freeze {
+    /// Id of the process the value belongs to.
+    pub pid: u32,
+    pub address: usize,
+    pub bytes: Vec<u8>,
+    /// Name of the frozen field, e.g. `Player.health`.
+    pub label: String,
+}
+
+pub struct FreezeList {
+    freezes: Arc<Mutex<Vec<Freeze>>>,
+    interval_ms: Arc<AtomicU64>,
+}
+
+impl FreezeList {
+    /// Spawns the thread that rewrites frozen values.
+    pub fn new(process: Arc<RwLock<Option<YProcess>>>, interval_ms: u64) -> Self {
+        let freezes = Arc::<Mutex<Vec<Freeze>>>::default();
+        let interval = Arc::new(AtomicU64::new(interval_ms.max(1)));
+
+        let (thread_freezes, thread_interval) = (freezes.clone(), interval.clone());
+        thread::spawn(move || loop {
+            thread::sleep(Duration::from_millis(
+                thread_interval.load(Ordering::Relaxed),
+            ));
+
+            let freezes = thread_freezes.lock().clone();
+            if freezes.is_empty() {
+                continue;
+            }
+
+            // Process might be currently replaced, in which case this tick is skipped.
+            let Some(process) = process.try_read() else {
+                continue;
+            };
+
+            if let Some(process) = process.as_ref() {
+                let pid = process.id();
+                for freeze in freezes.iter().filter(|f| f.pid == pid) {
+                    process.write(freeze.address, &freeze.bytes);
+                }
+            }
+        });
+
+        Self {
+            freezes,
+            interval_ms: interval,
+        }
+    }
+
+    pub fn freeze(&self, freeze: Freeze) {
+        let mut freezes = self.freezes.lock();
+        freezes.retain(|f| f.pid != freeze.pid || f.address != freeze.address);
+        freezes.push(freeze);
+    }
+
+    pub fn unfreeze(&self, pid: u32, address: usize) {
+        self.freezes
+            .lock()
+            .retain(|f| f.pid != pid || f.address != address);
+    }
+
+    pub fn is_frozen(&self, pid: u32, address: usize) -> bool {
+        self.freezes
+            .lock()
+            .iter()
+            .any(|f| f.pid == pid && f.address == address)
+    }
+
+    pub fn list(&self) -> Vec<Freeze> {
+        self.freezes.lock().clone()
+    }
+
+    pub fn clear(&self) {
+        self.freezes.lock().clear();
+    }
+
+    pub fn interval_ms(&self) -> u64 {
+        self.interval_ms.load(Ordering::Relaxed)
+    }
+
+    pub fn set_interval_ms(&self, interval_ms: u64) {
+        self.interval_ms
+            .store(interval_ms.max(1), Ordering::Relaxed);
+    }
+}
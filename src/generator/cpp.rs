@@ -1,51 +1,116 @@
-use super::Generator;
-use crate::field::FieldKind;
-use std::{borrow::Cow, mem::take};
+use super::{
+    comment_lines, cpp_path, pointer_class, pointer_comment, qualify_metadata, Generator, Layout,
+};
+use crate::field::{FieldKind, Semantic};
+use std::{borrow::Cow, collections::HashMap, mem::take};
 
 pub struct CppGenerator {
     predecls: String,
     main: String,
     offset: usize,
     last_offset: usize,
+    /// Name of the union that is currently being generated.
+    union: Option<String>,
+    /// Namespaces of all classes by their names.
+    namespaces: HashMap<String, String>,
+    /// Namespace that is currently open, empty for the global one.
+    namespace: String,
+    /// Name of the class that is currently being generated.
+    class: String,
+    layout: Option<Layout>,
+    /// Assertions of the class layout, placed after the class.
+    asserts: String,
+    /// Comment of the class or the field added next.
+    comment: String,
 }
 
 impl Default for CppGenerator {
     fn default() -> Self {
         Self {
             predecls: format!(
-                "// Generated by YClass {}\n// Made by @ItsEthra\n\n#include <cstdint>\n\n",
+                "// Generated by YClass {}\n// Made by @ItsEthra\n\n#include <cstddef>\n#include <cstdint>\n#include <ctime>\n\n",
                 env!("YCLASS_VERSION")
             ),
             main: "".to_owned(),
             offset: 0,
             last_offset: 0,
+            union: None,
+            namespaces: HashMap::new(),
+            namespace: String::new(),
+            class: String::new(),
+            layout: None,
+            asserts: String::new(),
+            comment: String::new(),
         }
     }
 }
 
 impl Generator for CppGenerator {
-    fn begin_class(&mut self, name: &str) {
-        self.predecls += &format!("class {name};\n");
-        self.main += &format!("class {name} {{\npublic:\n");
+    fn begin_class(&mut self, name: &str, base: Option<&str>) {
+        match self.namespace.as_str() {
+            "" => self.predecls += &format!("class {name};\n"),
+            ns => self.predecls += &format!("namespace {ns} {{ class {name}; }}\n"),
+        }
+        let align = match self.layout.and_then(|l| l.alignment) {
+            Some(alignment) => format!("alignas({alignment}) "),
+            None => String::new(),
+        };
+        self.main += &comment_lines(&take(&mut self.comment), "//");
+        match base.map(|b| self.qualify(b)) {
+            Some(base) => {
+                self.main += &format!("class {align}{name} : public {base} {{\npublic:\n")
+            }
+            None => self.main += &format!("class {align}{name} {{\npublic:\n"),
+        }
+        self.class = name.to_owned();
     }
 
     fn end_class(&mut self) {
-        self.main += "};\n\n";
+        self.main += "};\n";
+        if let Some(layout) = self.layout.take() {
+            let name = &self.class;
+            self.main += &format!("static_assert(sizeof({name}) == 0x{:X});\n", layout.size);
+            if let Some(alignment) = layout.alignment {
+                self.main += &format!("static_assert(alignof({name}) == {alignment});\n");
+            }
+            self.main += &take(&mut self.asserts);
+        }
+        self.main += "\n";
         self.offset = 0;
         self.last_offset = 0;
     }
 
     fn add_field(&mut self, name: &str, kind: FieldKind, metadata: Option<&str>) {
-        let size = kind.size();
-        if self.offset != self.last_offset {
-            self.main += &format!(
-                "    char _pad0x{:x}[0x{:x}];\n",
-                self.offset,
-                self.offset - self.last_offset
-            );
+        let metadata = qualify_metadata(kind, metadata, |c| self.qualify(c));
+        let metadata = metadata.as_deref();
+        if self.union.is_some() {
+            self.main += &comment_lines(&take(&mut self.comment), "        //");
+            self.main += &format!("        {};\n", declare(name, kind, metadata));
+            return;
         }
 
-        self.main += &format!("    {} {name};\n", kind_to_type(kind, metadata));
+        let size = kind.size();
+        self.add_padding();
+        self.assert_offset(name);
+        self.main += &comment_lines(&take(&mut self.comment), "    //");
+        match Semantic::from_field(kind, metadata) {
+            Some(semantic) => {
+                self.main += &format!(
+                    "    {} {name}; // {}\n",
+                    semantic
+                        .cpp_type(kind)
+                        .map(Cow::from)
+                        .unwrap_or_else(|| kind_to_type(kind, metadata)),
+                    semantic.label()
+                );
+            }
+            None => match pointer_comment(kind, metadata) {
+                Some(comment) => {
+                    self.main += &format!("    {}; // {comment}\n", declare(name, kind, metadata))
+                }
+                None => self.main += &format!("    {};\n", declare(name, kind, metadata)),
+            },
+        }
 
         self.offset += size;
         self.last_offset = self.offset;
@@ -55,14 +120,110 @@ impl Generator for CppGenerator {
         self.offset += offset;
     }
 
+    fn begin_union(&mut self, name: &str, tag_offset: Option<usize>) {
+        self.add_padding();
+        self.assert_offset(name);
+        self.main += &comment_lines(&take(&mut self.comment), "    //");
+        if let Some(tag) = tag_offset {
+            self.main += &format!("    // Member is selected by the tag at +0x{tag:X}\n");
+        }
+        self.main += "    union {\n";
+        self.union = Some(name.to_owned());
+    }
+
+    fn end_union(&mut self, size: usize) {
+        if let Some(name) = self.union.take() {
+            self.main += &format!("    }} {name};\n");
+        }
+
+        self.offset += size;
+        self.last_offset = self.offset;
+    }
+
     fn finilize(&mut self) -> String {
+        self.enter_namespace("");
         take(&mut self.predecls) + "\n" + &take(&mut self.main)
     }
+
+    fn set_class_namespaces(&mut self, namespaces: HashMap<String, String>) {
+        self.namespaces = namespaces;
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = Some(layout);
+    }
+
+    fn set_comment(&mut self, comment: &str) {
+        self.comment = comment.to_owned();
+    }
+
+    fn enter_namespace(&mut self, namespace: &str) {
+        if self.namespace == namespace {
+            return;
+        }
+
+        if !self.namespace.is_empty() {
+            self.main += &format!("}} // namespace {}\n\n", self.namespace);
+        }
+        if !namespace.is_empty() {
+            self.main += &format!("namespace {namespace} {{\n\n");
+        }
+        self.namespace = namespace.to_owned();
+    }
+}
+
+impl CppGenerator {
+    /// Name of the class as seen from the current namespace.
+    fn qualify(&self, class: &str) -> String {
+        match self.namespaces.get(class) {
+            Some(namespace) => cpp_path(&self.namespace, namespace, class),
+            None => class.to_owned(),
+        }
+    }
+
+    fn assert_offset(&mut self, field: &str) {
+        if let Some(layout) = self.layout {
+            self.asserts += &format!(
+                "static_assert(offsetof({}, {field}) == 0x{:X});\n",
+                self.class,
+                layout.base_size + self.offset
+            );
+        }
+    }
+
+    fn add_padding(&mut self) {
+        if self.offset != self.last_offset {
+            self.main += &format!(
+                "    char _pad0x{:x}[0x{:x}];\n",
+                self.offset,
+                self.offset - self.last_offset
+            );
+        }
+    }
+}
+
+/// Declares a field, function pointers need their name inside of the type.
+fn declare(name: &str, kind: FieldKind, metadata: Option<&str>) -> String {
+    if kind == FieldKind::FnPtr {
+        // Signature is expected to be `ret(args)`.
+        if let Some((ret, args)) = metadata
+            .and_then(|s| s.split_once('('))
+            .filter(|(ret, args)| !ret.trim().is_empty() && args.ends_with(')'))
+        {
+            return format!("{} (*{name})({args}", ret.trim());
+        }
+    }
+
+    format!("{} {name}", kind_to_type(kind, metadata))
 }
 
 fn kind_to_type(kind: FieldKind, metadata: Option<&str>) -> Cow<'static, str> {
     match kind {
-        FieldKind::Unk8 | FieldKind::Unk16 | FieldKind::Unk32 | FieldKind::Unk64 => unreachable!(),
+        FieldKind::Unk8
+        | FieldKind::Unk16
+        | FieldKind::Unk32
+        | FieldKind::Unk64
+        | FieldKind::Union => unreachable!(),
         FieldKind::I8 => "int8_t".into(),
         FieldKind::U8 => "uint8_t".into(),
         FieldKind::I16 => "int16_t".into(),
@@ -73,8 +234,11 @@ fn kind_to_type(kind: FieldKind, metadata: Option<&str>) -> Cow<'static, str> {
         FieldKind::U64 => "uint64_t".into(),
         FieldKind::F32 => "float".into(),
         FieldKind::F64 => "double".into(),
-        FieldKind::Ptr => format!("{}*", metadata.unwrap_or("void")).into(),
+        FieldKind::Ptr => format!("{}*", pointer_class(metadata).unwrap_or("void")).into(),
+        FieldKind::RelPtr32 => "int32_t".into(),
+        FieldKind::RelPtr64 => "int64_t".into(),
         FieldKind::StrPtr => "const char*".into(),
+        FieldKind::FnPtr => "void*".into(),
         FieldKind::Bool => "bool".into(),
     }
 }
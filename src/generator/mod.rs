@@ -1,18 +1,126 @@
-use crate::field::FieldKind;
+use crate::field::{split_pointer_metadata, FieldKind};
+use std::collections::HashMap;
 
 mod rust;
 pub use rust::*;
 mod cpp;
 pub use cpp::*;
 
+/// Layout of the class, passed before the class is begun.
+#[derive(Debug, Clone, Copy)]
+pub struct Layout {
+    /// Offset own fields of the class start at.
+    pub base_size: usize,
+    pub size: usize,
+    /// Declared alignment, `None` for the natural one.
+    pub alignment: Option<usize>,
+}
+
 pub trait Generator {
-    fn begin_class(&mut self, name: &str);
+    /// Offsets of the fields are relative to the end of the `base` class.
+    fn begin_class(&mut self, name: &str, base: Option<&str>);
     fn end_class(&mut self);
 
     fn add_field(&mut self, name: &str, kind: FieldKind, metadata: Option<&str>);
     fn add_offset(&mut self, offset: usize);
 
+    /// Fields added until [`Generator::end_union`] is called are members of the union.
+    fn begin_union(&mut self, name: &str, tag_offset: Option<usize>);
+    fn end_union(&mut self, size: usize);
+
     fn finilize(&mut self) -> String;
+
+    /// Maps names of all classes to their namespaces, used to refer to classes
+    /// of other namespaces.
+    fn set_class_namespaces(&mut self, _namespaces: HashMap<String, String>) {}
+    /// Classes begun afterwards are placed into the namespace, empty for the global one.
+    fn enter_namespace(&mut self, _namespace: &str) {}
+    /// Layout of the class begun next, used to assert sizes and offsets of fields.
+    fn set_layout(&mut self, _layout: Layout) {}
+    /// Comment of the class, the field or the union added next, empty if there is none.
+    fn set_comment(&mut self, _comment: &str) {}
+}
+
+/// Turns every line of the comment into a line starting with `prefix`, e.g. `    ///`.
+fn comment_lines(comment: &str, prefix: &str) -> String {
+    comment
+        .lines()
+        .map(|line| format!("{}\n", format!("{prefix} {line}").trim_end()))
+        .collect()
+}
+
+/// Replaces the class pointer metadata refers to with `qualify(class)`.
+fn qualify_metadata(
+    kind: FieldKind,
+    metadata: Option<&str>,
+    qualify: impl Fn(&str) -> String,
+) -> Option<String> {
+    let metadata = metadata?;
+    if !matches!(
+        kind,
+        FieldKind::Ptr | FieldKind::RelPtr32 | FieldKind::RelPtr64
+    ) {
+        return Some(metadata.to_owned());
+    }
+
+    Some(match split_pointer_metadata(metadata) {
+        (class, 0) => qualify(class),
+        (class, offset) => format!("{}+{offset:X}", qualify(class)),
+    })
+}
+
+/// Path to the class `name` in the namespace `to` from the namespace `from`
+/// using `super::` to go up.
+fn rust_path(from: &str, to: &str, name: &str) -> String {
+    let from = from
+        .split("::")
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>();
+    let to = to.split("::").filter(|p| !p.is_empty()).collect::<Vec<_>>();
+    let common = from
+        .iter()
+        .zip(to.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut path = "super::".repeat(from.len() - common);
+    for part in &to[common..] {
+        path += part;
+        path += "::";
+    }
+    path + name
+}
+
+/// Path to the class `name` in the namespace `to`, fully qualified unless `to` is `from`.
+fn cpp_path(from: &str, to: &str, name: &str) -> String {
+    match to {
+        _ if from == to => name.to_owned(),
+        "" => format!("::{name}"),
+        to => format!("::{to}::{name}"),
+    }
+}
+
+/// Describes the target of relative and interior pointers.
+fn pointer_comment(kind: FieldKind, metadata: Option<&str>) -> Option<String> {
+    let (class, offset) = metadata.map(split_pointer_metadata)?;
+    let target = match offset {
+        0 => class.to_owned(),
+        offset => format!("{class}+0x{offset:X}"),
+    };
+
+    match kind {
+        FieldKind::RelPtr32 | FieldKind::RelPtr64 => Some(format!("relative -> {target}")),
+        FieldKind::Ptr if offset != 0 => Some(format!("-> {target}")),
+        _ => None,
+    }
+}
+
+/// Class a pointer points to, `None` for interior pointers.
+fn pointer_class(metadata: Option<&str>) -> Option<&str> {
+    match metadata.map(split_pointer_metadata) {
+        Some((class, 0)) => Some(class),
+        _ => None,
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
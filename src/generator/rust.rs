@@ -1,48 +1,125 @@
-use super::Generator;
-use crate::field::FieldKind;
-use std::borrow::Cow;
+use super::{
+    comment_lines, pointer_class, pointer_comment, qualify_metadata, rust_path, Generator, Layout,
+};
+use crate::{
+    class::{child_namespace, join_namespace},
+    field::{FieldKind, Semantic},
+};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Write,
+    mem::take,
+};
 
+#[derive(Default)]
 pub struct RustGenerator {
+    /// Definition of the current class.
     text: String,
+    /// Definitions of classes by their namespaces, nested into modules at the end.
+    modules: BTreeMap<String, String>,
+    /// Namespaces of all classes by their names.
+    namespaces: HashMap<String, String>,
+    namespace: String,
+    layout: Option<Layout>,
+    /// Assertions of the class layout, placed after the class.
+    asserts: String,
+    /// Definitions of unions used by the current class.
+    unions: String,
+    class: String,
+    /// Name of the field of the union that is currently being generated.
+    union: Option<String>,
+    /// Comment of the field of the union, written when the union ends.
+    union_comment: String,
+    /// Comment of the class or the field added next.
+    comment: String,
     last_offset: usize,
     offset: usize,
 }
 
-impl Default for RustGenerator {
-    fn default() -> Self {
-        Self {
-            text: format!(
-                "// Generated by YClass {}\n// Made by @ItsEthra\n\n",
-                env!("YCLASS_VERSION")
-            ),
-            last_offset: 0,
-            offset: 0,
-        }
-    }
-}
-
 impl Generator for RustGenerator {
-    fn begin_class(&mut self, name: &str) {
-        self.text += &format!("#[repr(C)]\npub struct {name} {{\n");
+    fn begin_class(&mut self, name: &str, base: Option<&str>) {
+        let repr = match self.layout.and_then(|l| l.alignment) {
+            Some(alignment) => format!("C, align({alignment})"),
+            None => "C".to_owned(),
+        };
+        self.text += &comment_lines(&take(&mut self.comment), "///");
+        self.text += &format!("#[repr({repr})]\npub struct {name} {{\n");
+        if let Some(base) = base {
+            self.text += &format!("    pub base: {},\n", self.qualify(base));
+        }
+        self.class = name.to_owned();
     }
 
     fn end_class(&mut self) {
-        self.text += "}\n\n";
+        self.text += "}\n";
+        if let Some(layout) = self.layout.take() {
+            self.text += &format!(
+                "const _: () = assert!(std::mem::size_of::<{}>() == 0x{:X});\n",
+                self.class, layout.size
+            );
+            if let Some(alignment) = layout.alignment {
+                self.text += &format!(
+                    "const _: () = assert!(std::mem::align_of::<{}>() == {alignment});\n",
+                    self.class
+                );
+            }
+            self.text += &take(&mut self.asserts);
+        }
+        self.text += "\n";
+        self.text += &take(&mut self.unions);
+        self.modules
+            .entry(self.namespace.clone())
+            .or_default()
+            .push_str(&take(&mut self.text));
         self.offset = 0;
         self.last_offset = 0;
     }
 
     fn add_field(&mut self, name: &str, kind: FieldKind, metadata: Option<&str>) {
-        let size = kind.size();
-        if self.offset != self.last_offset {
-            self.text += &format!(
-                "    _pad_0x{:x}: [u8; 0x{:x}],\n",
-                self.offset,
-                self.offset - self.last_offset
-            );
+        let metadata = qualify_metadata(kind, metadata, |c| self.qualify(c));
+        let metadata = metadata.as_deref();
+        if self.union.is_some() {
+            self.unions += &comment_lines(&take(&mut self.comment), "    ///");
+            self.unions += &format!("    pub {name}: {},\n", kind_to_type(kind, metadata));
+            return;
         }
 
-        self.text += &format!("    pub {name}: {},\n", kind_to_type(kind, metadata));
+        let size = kind.size();
+        self.add_padding();
+        self.assert_offset(name);
+        self.text += &comment_lines(&take(&mut self.comment), "    ///");
+        match Semantic::from_field(kind, metadata) {
+            Some(semantic) => {
+                self.text += &format!(
+                    "    pub {name}: {}, // {}\n",
+                    semantic
+                        .rust_type(kind)
+                        .map(Cow::from)
+                        .unwrap_or_else(|| kind_to_type(kind, metadata)),
+                    semantic.label()
+                );
+            }
+            None if kind == FieldKind::FnPtr && metadata.is_some() => {
+                // C signature can't be translated, so it is left for the user.
+                self.text += &format!(
+                    "    pub {name}: {}, // {}\n",
+                    kind_to_type(kind, metadata),
+                    metadata.unwrap()
+                );
+            }
+            None => match pointer_comment(kind, metadata) {
+                Some(comment) => {
+                    self.text += &format!(
+                        "    pub {name}: {}, // {comment}\n",
+                        kind_to_type(kind, metadata)
+                    )
+                }
+                None => {
+                    self.text += &format!("    pub {name}: {},\n", kind_to_type(kind, metadata))
+                }
+            },
+        }
 
         self.offset += size;
         self.last_offset = self.offset;
@@ -52,14 +129,120 @@ impl Generator for RustGenerator {
         self.offset += offset;
     }
 
+    fn begin_union(&mut self, name: &str, tag_offset: Option<usize>) {
+        self.add_padding();
+        self.assert_offset(name);
+        if let Some(tag) = tag_offset {
+            self.unions += &format!("/// Member is selected by the tag at +0x{tag:X}\n");
+        }
+        self.unions += &format!(
+            "#[repr(C)]\n#[allow(non_camel_case_types)]\npub union {}_{name} {{\n",
+            self.class
+        );
+        self.union = Some(name.to_owned());
+        self.union_comment = take(&mut self.comment);
+    }
+
+    fn end_union(&mut self, size: usize) {
+        if let Some(name) = self.union.take() {
+            self.unions += "}\n\n";
+            self.text += &comment_lines(&take(&mut self.union_comment), "    ///");
+            self.text += &format!("    pub {name}: {}_{name},\n", self.class);
+        }
+
+        self.offset += size;
+        self.last_offset = self.offset;
+    }
+
     fn finilize(&mut self) -> String {
-        std::mem::take(&mut self.text)
+        let mut out = format!(
+            "// Generated by YClass {}\n// Made by @ItsEthra\n\n",
+            env!("YCLASS_VERSION")
+        );
+        write_module(&mut out, &take(&mut self.modules), "", 0);
+        out
+    }
+
+    fn set_class_namespaces(&mut self, namespaces: HashMap<String, String>) {
+        self.namespaces = namespaces;
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = Some(layout);
+    }
+
+    fn enter_namespace(&mut self, namespace: &str) {
+        self.namespace = namespace.to_owned();
+    }
+
+    fn set_comment(&mut self, comment: &str) {
+        self.comment = comment.to_owned();
+    }
+}
+
+impl RustGenerator {
+    /// Path to the class from the current namespace.
+    fn qualify(&self, class: &str) -> String {
+        match self.namespaces.get(class) {
+            Some(namespace) => rust_path(&self.namespace, namespace, class),
+            None => class.to_owned(),
+        }
+    }
+
+    fn assert_offset(&mut self, field: &str) {
+        if let Some(layout) = self.layout {
+            self.asserts += &format!(
+                "const _: () = assert!(std::mem::offset_of!({}, {field}) == 0x{:X});\n",
+                self.class,
+                layout.base_size + self.offset
+            );
+        }
+    }
+
+    fn add_padding(&mut self) {
+        if self.offset != self.last_offset {
+            self.text += &format!(
+                "    _pad_0x{:x}: [u8; 0x{:x}],\n",
+                self.offset,
+                self.offset - self.last_offset
+            );
+        }
+    }
+}
+
+/// Writes classes of the namespace followed by modules nested into it.
+fn write_module(
+    out: &mut String,
+    modules: &BTreeMap<String, String>,
+    namespace: &str,
+    depth: usize,
+) {
+    let indent = "    ".repeat(depth);
+    for line in modules.get(namespace).into_iter().flat_map(|m| m.lines()) {
+        match line {
+            "" => out.push('\n'),
+            line => _ = writeln!(out, "{indent}{line}"),
+        }
+    }
+
+    let children = modules
+        .keys()
+        .filter_map(|ns| child_namespace(namespace, ns))
+        .collect::<BTreeSet<_>>();
+    for child in children {
+        _ = writeln!(out, "{indent}pub mod {child} {{");
+        write_module(out, modules, &join_namespace(namespace, child), depth + 1);
+        _ = writeln!(out, "{indent}}}\n");
     }
 }
 
 fn kind_to_type(kind: FieldKind, metadata: Option<&str>) -> Cow<'static, str> {
     match kind {
-        FieldKind::Unk8 | FieldKind::Unk16 | FieldKind::Unk32 | FieldKind::Unk64 => unreachable!(),
+        FieldKind::Unk8
+        | FieldKind::Unk16
+        | FieldKind::Unk32
+        | FieldKind::Unk64
+        | FieldKind::Union => unreachable!(),
         FieldKind::I8 => "i8".into(),
         FieldKind::U8 => "u8".into(),
         FieldKind::I16 => "i16".into(),
@@ -70,8 +253,14 @@ fn kind_to_type(kind: FieldKind, metadata: Option<&str>) -> Cow<'static, str> {
         FieldKind::U64 => "u64".into(),
         FieldKind::F32 => "f32".into(),
         FieldKind::F64 => "f64".into(),
-        FieldKind::Ptr => format!("Option<&'static {}>", metadata.unwrap_or("()")).into(),
+        FieldKind::Ptr => match (metadata, pointer_class(metadata)) {
+            (Some(_), None) => "*const ()".into(),
+            (_, class) => format!("Option<&'static {}>", class.unwrap_or("()")).into(),
+        },
+        FieldKind::RelPtr32 => "i32".into(),
+        FieldKind::RelPtr64 => "i64".into(),
         FieldKind::StrPtr => "*const u8".into(),
+        FieldKind::FnPtr => "Option<unsafe extern \"C\" fn()>".into(),
         FieldKind::Bool => "bool".into(),
     }
 }
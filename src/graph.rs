@@ -0,0 +1,242 @@
+//! This module builds the graph of relationships between classes
+//! and exports it as Graphviz DOT or SVG.
+use crate::{
+    class::{ClassId, ClassList},
+    field::FieldKind,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Write,
+};
+
+/// Size of a node in the layout and in the exported SVG.
+pub const NODE_SIZE: (f32, f32) = (160., 44.);
+/// Space between layers and between nodes of a layer.
+const SPACING: (f32, f32) = (60., 90.);
+
+pub struct Node {
+    pub id: ClassId,
+    pub name: String,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EdgeKind {
+    /// Field of the class points to the other class.
+    Pointer,
+    /// Class is derived from the other class.
+    Inheritance,
+}
+
+pub struct Edge {
+    pub from: ClassId,
+    pub to: ClassId,
+    pub kind: EdgeKind,
+    /// Offset of the pointer inside of the class, `None` for inheritance.
+    pub offset: Option<usize>,
+}
+
+impl Edge {
+    pub fn label(&self) -> String {
+        match self.offset {
+            Some(offset) => format!("+{offset:X}"),
+            None => String::new(),
+        }
+    }
+}
+
+pub struct ClassGraph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl ClassGraph {
+    pub fn build(list: &ClassList) -> Self {
+        let nodes = list
+            .classes()
+            .iter()
+            .map(|c| Node {
+                id: c.id(),
+                name: c.name.clone(),
+                size: list.base_size(c.id()) + c.own_size(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut edges = vec![];
+        for class in list.classes() {
+            if let Some(base) = class.base(list.classes()) {
+                edges.push(Edge {
+                    from: class.id(),
+                    to: base.id(),
+                    kind: EdgeKind::Inheritance,
+                    offset: None,
+                });
+            }
+
+            let mut offset = list.base_size(class.id());
+            for field in class.fields.iter() {
+                // Unions don't have a single target, but their members might point somewhere.
+                let targets = match field.target_class() {
+                    Some(target) => vec![target],
+                    None if field.kind() == FieldKind::Union => nodes
+                        .iter()
+                        .filter(|n| field.points_to(n.id))
+                        .map(|n| n.id)
+                        .collect(),
+                    None => vec![],
+                };
+
+                edges.extend(
+                    targets
+                        .into_iter()
+                        .filter(|t| list.by_id(*t).is_some())
+                        .map(|to| Edge {
+                            from: class.id(),
+                            to,
+                            kind: EdgeKind::Pointer,
+                            offset: Some(offset),
+                        }),
+                );
+                offset += field.size();
+            }
+        }
+
+        Self { nodes, edges }
+    }
+
+    /// Places nodes in layers, every class is placed below the classes referencing it.
+    /// Returns positions of top left corners of the nodes.
+    pub fn layout(&self) -> HashMap<ClassId, (f32, f32)> {
+        let mut depth = HashMap::new();
+        let mut queue = self
+            .nodes
+            .iter()
+            .filter(|n| !self.edges.iter().any(|e| e.to == n.id && e.from != n.id))
+            .map(|n| (n.id, 0))
+            .collect::<VecDeque<_>>();
+
+        while let Some((id, d)) = queue.pop_front() {
+            if depth.contains_key(&id) {
+                continue;
+            }
+            depth.insert(id, d);
+
+            for edge in self.edges.iter().filter(|e| e.from == id) {
+                queue.push_back((edge.to, d + 1));
+            }
+        }
+
+        // Classes only reachable through cycles are placed on the first layer.
+        let mut layers = HashMap::<usize, usize>::new();
+        self.nodes
+            .iter()
+            .map(|n| {
+                let d = depth.get(&n.id).copied().unwrap_or_default();
+                let i = layers.entry(d).or_default();
+                *i += 1;
+
+                let x = (*i - 1) as f32 * (NODE_SIZE.0 + SPACING.0);
+                let y = d as f32 * (NODE_SIZE.1 + SPACING.1);
+                (n.id, (x, y))
+            })
+            .collect()
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph classes {\n    node [shape=box];\n");
+
+        for node in self.nodes.iter() {
+            _ = writeln!(
+                out,
+                "    n{} [label=\"{}\\n{:#X} bytes\"];",
+                node.id,
+                escape(&node.name),
+                node.size
+            );
+        }
+
+        for edge in self.edges.iter() {
+            let style = match edge.kind {
+                EdgeKind::Pointer => format!("label=\"{}\"", edge.label()),
+                EdgeKind::Inheritance => "arrowhead=empty, style=dashed".into(),
+            };
+            _ = writeln!(out, "    n{} -> n{} [{style}];", edge.from, edge.to);
+        }
+
+        out + "}\n"
+    }
+
+    pub fn to_svg(&self, positions: &HashMap<ClassId, (f32, f32)>) -> String {
+        let (w, h) = positions.values().fold((0f32, 0f32), |(w, h), (x, y)| {
+            (w.max(x + NODE_SIZE.0), h.max(y + NODE_SIZE.1))
+        });
+        let margin = 20.;
+        let pos = |id: &ClassId| {
+            let (x, y) = positions.get(id).copied().unwrap_or_default();
+            (x + margin, y + margin)
+        };
+
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"12\">\n",
+            w + margin * 2.,
+            h + margin * 2.
+        );
+        out += "<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto\"><path d=\"M0,0 L10,5 L0,10 z\"/></marker></defs>\n";
+
+        for edge in self.edges.iter() {
+            let (from, to) = (pos(&edge.from), pos(&edge.to));
+            let (x1, y1) = (from.0 + NODE_SIZE.0 / 2., from.1 + NODE_SIZE.1);
+            let (x2, y2) = (to.0 + NODE_SIZE.0 / 2., to.1);
+            let dash = match edge.kind {
+                EdgeKind::Pointer => "",
+                EdgeKind::Inheritance => " stroke-dasharray=\"4\"",
+            };
+            _ = writeln!(
+                out,
+                "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"black\"{dash} marker-end=\"url(#arrow)\"/>"
+            );
+            _ = writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\">{}</text>",
+                (x1 + x2) / 2. + 4.,
+                (y1 + y2) / 2.,
+                edge.label()
+            );
+        }
+
+        for node in self.nodes.iter() {
+            let (x, y) = pos(&node.id);
+            _ = writeln!(
+                out,
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"{}\" fill=\"white\" stroke=\"black\"/>",
+                NODE_SIZE.0, NODE_SIZE.1
+            );
+            _ = writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                x + NODE_SIZE.0 / 2.,
+                y + 18.,
+                escape_xml(&node.name)
+            );
+            _ = writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" fill=\"gray\">{:#X} bytes</text>",
+                x + NODE_SIZE.0 / 2.,
+                y + 34.,
+                node.size
+            );
+        }
+
+        out + "</svg>\n"
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,194 @@
+//! This module guesses kinds of unknown fields out of sampled memory.
+//! Every sample is a copy of the field's bytes, taken from different instances of the class
+//! or from the same instance at different times. A guess is only made if all samples agree.
+use crate::{
+    class::{ClassId, ClassList},
+    field::{allocate_padding, FieldId, FieldKind},
+    history::Command,
+    process::YProcess,
+};
+use std::collections::HashMap;
+
+/// Pointers below this address are most likely just integers.
+const MIN_POINTER: u64 = 0x10000;
+/// Integers with absolute value below this are considered small.
+const MAX_SMALL_INT: i64 = 1 << 20;
+/// Minimum amount of printable characters pointed to by a string pointer.
+const MIN_STRING_LEN: usize = 4;
+
+pub type Guesses = HashMap<FieldId, Guess>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Guess {
+    /// `None` means the field is padding and should stay unknown.
+    pub kind: Option<FieldKind>,
+    /// Short reason of the guess, e.g. `vtable`.
+    pub label: &'static str,
+}
+
+impl Guess {
+    pub fn describe(&self) -> String {
+        match self.kind {
+            Some(kind) => format!("{kind:?} ({})", self.label),
+            None => self.label.to_owned(),
+        }
+    }
+}
+
+/// Guesses the kind of the field of `size` bytes. All samples must be `size` bytes long.
+pub fn infer(process: &YProcess, size: usize, samples: &[&[u8]]) -> Option<Guess> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    if samples.iter().all(|s| s.iter().all(|b| *b == 0)) {
+        return Some(guess(None, "padding"));
+    }
+
+    if size == 8 {
+        if let Some(guess) = infer_pointer(process, samples) {
+            return Some(guess);
+        }
+    }
+
+    let all = |f: &dyn Fn(&[u8]) -> bool| samples.iter().all(|s| f(s));
+
+    // Wider fields of 0 and 1 are more likely integers.
+    if size == 1 && all(&|s| s[0] <= 1) {
+        return Some(guess(Some(FieldKind::Bool), "0 or 1"));
+    }
+
+    if size == 8 && all(&|s| is_zero_or(plausible_f64(f64::from_le_bytes(to_array(s))))) {
+        return Some(guess(Some(FieldKind::F64), "float"));
+    }
+
+    if size >= 4 && all(&|s| is_zero_or(plausible_f32(f32::from_le_bytes(to_array(s))))) {
+        return Some(guess(Some(FieldKind::F32), "float"));
+    }
+
+    let negative = || samples.iter().any(|s| i64::from_le_bytes(to_array(s)) < 0);
+    if size == 8 && negative() && all(&|s| small_int(i64::from_le_bytes(to_array(s)))) {
+        return Some(guess(Some(FieldKind::I64), "small int"));
+    }
+
+    if size >= 4 && all(&|s| small_int(i32::from_le_bytes(to_array(s)) as i64)) {
+        return Some(guess(Some(FieldKind::I32), "small int"));
+    }
+
+    None
+}
+
+fn infer_pointer(process: &YProcess, samples: &[&[u8]]) -> Option<Guess> {
+    let values = samples
+        .iter()
+        .map(|s| u64::from_le_bytes(to_array(s)))
+        .collect::<Vec<_>>();
+
+    // Null pointers are fine, as long as some samples point somewhere.
+    let target = *values.iter().find(|v| **v != 0)?;
+    if !values
+        .iter()
+        .all(|v| *v == 0 || (*v >= MIN_POINTER && process.is_mapped(*v as usize)))
+    {
+        return None;
+    }
+
+    if process.is_executable(target as usize) {
+        return Some(guess(Some(FieldKind::FnPtr), "code"));
+    }
+
+    let mut buf = [0; 64];
+    process.read(target as usize, &mut buf);
+
+    let first = u64::from_le_bytes(to_array(&buf));
+    if first >= MIN_POINTER && process.is_executable(first as usize) {
+        return Some(guess(Some(FieldKind::Ptr), "vtable"));
+    }
+
+    let printable = buf
+        .iter()
+        .take_while(|b| b.is_ascii_graphic() || **b == b' ')
+        .count();
+    if printable >= MIN_STRING_LEN && buf.get(printable) == Some(&0) {
+        return Some(guess(Some(FieldKind::StrPtr), "string"));
+    }
+
+    Some(guess(Some(FieldKind::Ptr), "readable memory"))
+}
+
+fn guess(kind: Option<FieldKind>, label: &'static str) -> Guess {
+    Guess { kind, label }
+}
+
+fn to_array<const N: usize>(sample: &[u8]) -> [u8; N] {
+    sample[..N].try_into().unwrap()
+}
+
+fn is_zero_or(plausible: Option<bool>) -> bool {
+    plausible.unwrap_or(true)
+}
+
+/// `None` for zero, which is plausible for any kind.
+fn plausible_f32(value: f32) -> Option<bool> {
+    if value == 0. {
+        return None;
+    }
+
+    Some(value.is_normal() && (1e-4..=1e7).contains(&value.abs()))
+}
+
+fn plausible_f64(value: f64) -> Option<bool> {
+    if value == 0. {
+        return None;
+    }
+
+    Some(value.is_normal() && (1e-6..=1e12).contains(&value.abs()))
+}
+
+fn small_int(value: i64) -> bool {
+    value.abs() < MAX_SMALL_INT
+}
+
+/// Replaces fields of the class with fields of the guessed kinds.
+/// Accepted guesses are removed, returns the inverse command.
+pub fn accept(
+    list: &mut ClassList,
+    guesses: &mut Guesses,
+    class_id: ClassId,
+    field_ids: &[FieldId],
+) -> Option<Command> {
+    let mut inverse = vec![];
+
+    for id in field_ids {
+        let Some(guess) = guesses.remove(id) else {
+            continue;
+        };
+        let Some(kind) = guess.kind else {
+            continue;
+        };
+
+        let class = list.by_id(class_id)?;
+        let Some(at) = class.fields.iter().position(|f| f.id() == *id) else {
+            continue;
+        };
+
+        // Guessed kind is never larger than the unknown field.
+        let mut fields = vec![kind.into_field(None)];
+        fields.extend(allocate_padding(class.fields[at].size() - kind.size()));
+
+        let command = Command::Splice {
+            class_id,
+            at,
+            len: 1,
+            fields,
+        };
+        inverse.extend(command.apply(list, None));
+    }
+
+    if inverse.is_empty() {
+        return None;
+    }
+
+    inverse.reverse();
+    Some(Command::Batch(inverse))
+}
@@ -1,17 +1,32 @@
+use super::{GuessWindow, ReferencesWindow};
 use crate::{
     app::is_valid_ident,
-    class::{Class, ClassId},
-    state::StateRef,
+    class::{child_namespace, is_valid_namespace, join_namespace, ClassId, ClassList},
+    field::{Field, FieldId},
+    history::Command,
+    state::{GlobalState, StateRef},
 };
 use eframe::{
-    egui::{Button, Context, Key, ScrollArea, SelectableLabel, SidePanel, TextEdit},
-    epaint::vec2,
+    egui::{
+        show_tooltip_at_pointer, Button, CollapsingHeader, Context, DragAndDrop, Key, Label,
+        Response, ScrollArea, SelectableLabel, Sense, SidePanel, TextEdit, Ui,
+    },
+    epaint::{vec2, StrokeKind},
+};
+use std::{
+    collections::BTreeSet,
+    mem::{replace, take},
 };
-use std::mem::take;
 
 enum RequestedAction {
     Delete(ClassId),
+    GuessTypes(ClassId),
+    References(ClassId),
+    SetBase(ClassId, Option<ClassId>),
+    SetNamespace(ClassId, String),
     ToggleSelection(ClassId),
+    /// Selects the class and the field that was found.
+    Jump(ClassId, Option<FieldId>),
 }
 
 struct ClassEditState {
@@ -24,6 +39,14 @@ pub struct ClassListPanel {
     new_class_buf: String,
     edit_state: Option<ClassEditState>,
     should_focus_edit: bool,
+    /// Text searched in names of classes, names and types of fields.
+    search: String,
+    /// Classes of a namespace are sorted by name instead of the creation order.
+    sort_by_name: bool,
+    /// Namespace entered in the context menu of a class.
+    namespace_buf: String,
+    guess_window: GuessWindow,
+    references_window: ReferencesWindow,
     state: StateRef,
 }
 
@@ -33,6 +56,11 @@ impl ClassListPanel {
             state,
             edit_state: None,
             should_focus_edit: false,
+            search: String::new(),
+            sort_by_name: false,
+            namespace_buf: String::new(),
+            guess_window: GuessWindow::new(state),
+            references_window: ReferencesWindow::new(state),
             new_class_buf: "".to_owned(),
         }
     }
@@ -62,10 +90,24 @@ impl ClassListPanel {
             ui.horizontal_top(|ui| {
                 let size = vec2(ui.available_width(), 18.);
                 if ui.add_sized(size, Button::new("Remove empty")).clicked() {
-                    state.class_list.remove_empty();
+                    let command = Command::Batch(
+                        state
+                            .class_list
+                            .empty_classes()
+                            .into_iter()
+                            .map(Command::RemoveClass)
+                            .collect(),
+                    );
+                    state.history.apply(command, &mut state.class_list, None);
                 }
             });
 
+            ui.checkbox(&mut self.sort_by_name, "Sort by name");
+            TextEdit::singleline(&mut self.search)
+                .desired_width(f32::INFINITY)
+                .hint_text("Search classes and fields")
+                .show(ui);
+
             ui.add_space(4.);
             ui.separator();
             ui.add_space(4.);
@@ -87,101 +129,87 @@ impl ClassListPanel {
                     state.toasts.error("Not a valid class name");
                     self.should_focus_edit = true;
                 } else {
-                    state.class_list.add_class(take(&mut self.new_class_buf));
+                    let id = state.class_list.add_class(take(&mut self.new_class_buf));
+                    state.history.push(Command::RemoveClass(id));
                     state.dummy = false;
                 }
             }
 
             ui.vertical_centered_justified(|ui| {
                 ScrollArea::vertical().show(ui, |ui| {
-                    let selected = state.class_list.selected();
                     let mut action = None;
 
-                    fn map_state<'s>(
-                        class: &mut Class,
-                        state: &'s mut ClassEditState,
-                    ) -> Option<(&'s mut String, &'s mut bool)> {
-                        let ClassEditState {
-                            request_focus,
-                            new_name,
-                            id,
-                        } = state;
-                        if *id == class.id() {
-                            Some((new_name, request_focus))
-                        } else {
-                            None
-                        }
-                    }
+                    if self.search.is_empty() {
+                        let namespaces = state.class_list.namespaces();
+                        let names = state
+                            .class_list
+                            .classes()
+                            .iter()
+                            .map(|c| (c.id(), c.name.clone()))
+                            .collect::<Vec<_>>();
+                        self.namespace_ui(ui, state, "", &namespaces, &names, &mut action);
 
-                    for class in state.class_list.classes_mut() {
-                        if let Some((edit_buf, request_focus)) =
-                            self.edit_state.as_mut().and_then(|s| map_state(class, s))
-                        {
-                            let r = TextEdit::singleline(edit_buf)
-                                .desired_width(f32::INFINITY)
-                                .hint_text("New name")
-                                .show(ui)
-                                .response;
-
-                            let first_frame = if *request_focus {
-                                r.request_focus();
-                                *request_focus = false;
-                                true
-                            } else {
-                                false
-                            };
-
-                            if r.clicked_elsewhere() && !first_frame {
-                                self.edit_state = None;
-                            } else if r.lost_focus() {
-                                if !is_valid_ident(&*edit_buf) {
-                                    state.toasts.error("Not a valid class name");
-                                    *request_focus = true;
-                                } else {
-                                    class.name = take(edit_buf);
-                                    self.edit_state = None;
-                                    state.dummy = false;
-                                }
-                            }
-                        } else {
+                        // Classes are moved out of namespaces by dropping them below the list.
+                        if DragAndDrop::has_payload_of_type::<ClassId>(ui.ctx()) {
                             let r = ui.add_sized(
                                 vec2(ui.available_width(), 24.),
-                                SelectableLabel::new(
-                                    selected.map(|j| class.id() == j).unwrap_or_default(),
-                                    &class.name,
-                                ),
+                                Label::new("Global namespace").sense(Sense::hover()),
                             );
-
-                            if r.clicked() {
-                                action = Some(RequestedAction::ToggleSelection(class.id()));
-                            }
-
-                            r.context_menu(|ui| {
-                                ui.set_width(80.);
-
-                                ui.vertical_centered_justified(|ui| {
-                                    if ui.button("Rename").clicked() {
-                                        ui.close_menu();
-
-                                        self.edit_state = Some(ClassEditState {
-                                            new_name: class.name.clone(),
-                                            request_focus: true,
-                                            id: class.id(),
-                                        });
-                                    }
-
-                                    if ui.button("Delete").clicked() {
-                                        ui.close_menu();
-
-                                        action = Some(RequestedAction::Delete(class.id()));
-                                    }
-                                });
-                            });
+                            drop_target(ui, &r, "", &mut action);
                         }
+                    } else {
+                        self.search_ui(ui, &state.class_list, &mut action);
                     }
 
                     match action.take()? {
-                        RequestedAction::Delete(cid) => state.class_list.delete_by_id(cid),
+                        RequestedAction::Delete(cid) => {
+                            if state.class_list.references(cid).is_empty() {
+                                state.history.apply(
+                                    Command::RemoveClass(cid),
+                                    &mut state.class_list,
+                                    None,
+                                );
+                            } else {
+                                self.references_window.delete(cid);
+                            }
+                        }
+                        RequestedAction::GuessTypes(cid) => self.guess_window.open(cid),
+                        RequestedAction::References(cid) => self.references_window.open(cid),
+                        RequestedAction::SetBase(cid, base) => {
+                            if base.is_some_and(|b| !state.class_list.can_set_base(cid, b)) {
+                                state
+                                    .toasts
+                                    .error("Class can't inherit from the class derived from it");
+                            } else {
+                                state.history.apply(
+                                    Command::SetBase {
+                                        class_id: cid,
+                                        base,
+                                    },
+                                    &mut state.class_list,
+                                    None,
+                                );
+                            }
+                        }
+                        RequestedAction::SetNamespace(cid, namespace) => {
+                            if !is_valid_namespace(&namespace) {
+                                state.toasts.error("Not a valid namespace");
+                            } else if state
+                                .class_list
+                                .by_id(cid)
+                                .is_some_and(|c| c.namespace != namespace)
+                            {
+                                state.history.apply(
+                                    Command::SetNamespace {
+                                        class_id: cid,
+                                        namespace,
+                                    },
+                                    &mut state.class_list,
+                                    None,
+                                );
+                                state.dummy = false;
+                            }
+                        }
                         RequestedAction::ToggleSelection(cid) => {
                             let selected = state.class_list.selected_mut();
                             if *selected == Some(cid) {
@@ -190,11 +218,299 @@ impl ClassListPanel {
                                 *selected = Some(cid);
                             }
                         }
+                        RequestedAction::Jump(cid, field_id) => state.select_field(cid, field_id),
                     }
 
                     Some(())
                 });
             });
         });
+
+        self.guess_window.show(ctx);
+        self.references_window.show(ctx);
+    }
+
+    /// Shows nested namespaces as collapsible folders followed by classes of the namespace.
+    fn namespace_ui(
+        &mut self,
+        ui: &mut Ui,
+        state: &mut GlobalState,
+        namespace: &str,
+        namespaces: &[String],
+        names: &[(ClassId, String)],
+        action: &mut Option<RequestedAction>,
+    ) {
+        let children = namespaces
+            .iter()
+            .filter_map(|ns| child_namespace(namespace, ns))
+            .collect::<BTreeSet<_>>();
+
+        for child in children {
+            let path = join_namespace(namespace, child);
+            let r = CollapsingHeader::new(child)
+                .id_salt(("_class_namespace", &path))
+                .default_open(true)
+                .show(ui, |ui| {
+                    self.namespace_ui(ui, state, &path, namespaces, names, action);
+                });
+            drop_target(ui, &r.header_response, &path, action);
+        }
+
+        let mut classes = state
+            .class_list
+            .classes()
+            .iter()
+            .filter(|c| c.namespace == namespace)
+            .map(|c| (c.id(), c.name.clone()))
+            .collect::<Vec<_>>();
+        if self.sort_by_name {
+            classes.sort_by_cached_key(|(_, name)| name.to_lowercase());
+        }
+
+        for (class_id, _) in classes {
+            self.class_ui(ui, state, class_id, namespaces, names, action);
+        }
+    }
+
+    fn class_ui(
+        &mut self,
+        ui: &mut Ui,
+        state: &mut GlobalState,
+        class_id: ClassId,
+        namespaces: &[String],
+        names: &[(ClassId, String)],
+        action: &mut Option<RequestedAction>,
+    ) {
+        let selected = state.class_list.selected();
+        let Some(class) = state.class_list.by_id_mut(class_id) else {
+            return;
+        };
+
+        if let Some(ClassEditState {
+            request_focus,
+            new_name: edit_buf,
+            ..
+        }) = self.edit_state.as_mut().filter(|s| s.id == class_id)
+        {
+            let r = TextEdit::singleline(edit_buf)
+                .desired_width(f32::INFINITY)
+                .hint_text("New name")
+                .show(ui)
+                .response;
+
+            let first_frame = if *request_focus {
+                r.request_focus();
+                *request_focus = false;
+                true
+            } else {
+                false
+            };
+
+            if r.clicked_elsewhere() && !first_frame {
+                self.edit_state = None;
+            } else if r.lost_focus() {
+                if !is_valid_ident(&*edit_buf) {
+                    state.toasts.error("Not a valid class name");
+                    *request_focus = true;
+                } else {
+                    state.history.push(Command::RenameClass {
+                        class_id,
+                        name: replace(&mut class.name, take(edit_buf)),
+                    });
+                    self.edit_state = None;
+                    state.dummy = false;
+                }
+            }
+            return;
+        }
+
+        let r = ui
+            .add_sized(
+                vec2(ui.available_width(), 24.),
+                SelectableLabel::new(selected == Some(class_id), &class.name),
+            )
+            .interact(Sense::click_and_drag());
+        let r = if class.note.is_empty() {
+            r
+        } else {
+            r.on_hover_text(&class.note)
+        };
+
+        // Classes are moved between namespaces by dragging them onto a folder or another class.
+        r.dnd_set_drag_payload(class_id);
+        if r.dragged() {
+            show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), r.id, |ui| ui.label(&class.name));
+        }
+        drop_target(ui, &r, &class.namespace, action);
+
+        if r.clicked() {
+            *action = Some(RequestedAction::ToggleSelection(class_id));
+        }
+
+        r.context_menu(|ui| {
+            ui.set_width(80.);
+
+            ui.vertical_centered_justified(|ui| {
+                if ui.button("Rename").clicked() {
+                    ui.close_menu();
+
+                    self.edit_state = Some(ClassEditState {
+                        new_name: class.name.clone(),
+                        request_focus: true,
+                        id: class_id,
+                    });
+                }
+
+                ui.menu_button("Base class", |ui| {
+                    if ui.selectable_label(class.base.is_none(), "None").clicked() {
+                        ui.close_menu();
+                        *action = Some(RequestedAction::SetBase(class_id, None));
+                    }
+
+                    for (id, name) in names.iter().filter(|(id, _)| *id != class_id) {
+                        if ui.selectable_label(class.base == Some(*id), name).clicked() {
+                            ui.close_menu();
+                            *action = Some(RequestedAction::SetBase(class_id, Some(*id)));
+                        }
+                    }
+                });
+
+                ui.menu_button("Namespace", |ui| {
+                    if ui
+                        .selectable_label(class.namespace.is_empty(), "Global")
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        *action = Some(RequestedAction::SetNamespace(class_id, String::new()));
+                    }
+
+                    for namespace in namespaces.iter() {
+                        if ui
+                            .selectable_label(class.namespace == *namespace, namespace)
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            *action =
+                                Some(RequestedAction::SetNamespace(class_id, namespace.clone()));
+                        }
+                    }
+
+                    ui.separator();
+                    let r = TextEdit::singleline(&mut self.namespace_buf)
+                        .hint_text("New namespace, e.g. game::ui")
+                        .show(ui)
+                        .response;
+                    if r.lost_focus()
+                        && ui.input(|i| i.key_pressed(Key::Enter))
+                        && !self.namespace_buf.is_empty()
+                    {
+                        ui.close_menu();
+                        *action = Some(RequestedAction::SetNamespace(
+                            class_id,
+                            take(&mut self.namespace_buf),
+                        ));
+                    }
+                });
+
+                ui.menu_button("Note", |ui| {
+                    if TextEdit::multiline(&mut class.note)
+                        .hint_text("Note")
+                        .show(ui)
+                        .response
+                        .changed()
+                    {
+                        state.dummy = false;
+                    }
+                });
+
+                if ui.button("Guess types").clicked() {
+                    ui.close_menu();
+
+                    *action = Some(RequestedAction::GuessTypes(class_id));
+                }
+
+                if ui.button("References").clicked() {
+                    ui.close_menu();
+
+                    *action = Some(RequestedAction::References(class_id));
+                }
+
+                if ui.button("Delete").clicked() {
+                    ui.close_menu();
+
+                    *action = Some(RequestedAction::Delete(class_id));
+                }
+            });
+        });
+    }
+
+    /// Lists classes and named fields matching the search, clicking a result selects it.
+    fn search_ui(&mut self, ui: &mut Ui, list: &ClassList, action: &mut Option<RequestedAction>) {
+        let query = self.search.to_lowercase();
+        let selected = list.selected();
+        let mut found = false;
+
+        for class in list.classes() {
+            let path = join_namespace(&class.namespace, &class.name);
+            if class.name.to_lowercase().contains(&query) {
+                found = true;
+                if ui
+                    .add(SelectableLabel::new(selected == Some(class.id()), &path))
+                    .clicked()
+                {
+                    *action = Some(RequestedAction::Jump(class.id(), None));
+                }
+            }
+
+            for field in class.fields.iter() {
+                let Some(name) = field.name() else {
+                    continue;
+                };
+
+                let ty = field_type(list, field.as_ref());
+                if !name.to_lowercase().contains(&query) && !ty.to_lowercase().contains(&query) {
+                    continue;
+                }
+
+                found = true;
+                if ui
+                    .add(SelectableLabel::new(false, format!("{path}.{name}: {ty}")))
+                    .clicked()
+                {
+                    *action = Some(RequestedAction::Jump(class.id(), Some(field.id())));
+                }
+            }
+        }
+
+        if !found {
+            ui.label("Nothing was found");
+        }
+    }
+}
+
+/// Moves the class dropped onto the widget into the namespace.
+fn drop_target(ui: &Ui, r: &Response, namespace: &str, action: &mut Option<RequestedAction>) {
+    if r.dnd_hover_payload::<ClassId>().is_some() {
+        ui.painter().rect_stroke(
+            r.rect,
+            2.,
+            ui.visuals().selection.stroke,
+            StrokeKind::Inside,
+        );
+    }
+
+    if let Some(class_id) = r.dnd_release_payload::<ClassId>() {
+        *action = Some(RequestedAction::SetNamespace(
+            *class_id,
+            namespace.to_owned(),
+        ));
+    }
+}
+
+/// Kind of the field searched for, pointers include the name of the class they point to.
+fn field_type(list: &ClassList, field: &dyn Field) -> String {
+    match field.target_class().and_then(|id| list.by_id(id)) {
+        Some(class) => format!("{:?} {}", field.kind(), class.name),
+        None => format!("{:?}", field.kind()),
     }
 }
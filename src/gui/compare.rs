@@ -0,0 +1,135 @@
+use crate::{
+    address::parse_address,
+    class::{Class, Instance},
+    context::InspectionContext,
+    field::FieldResponse,
+};
+use eframe::{
+    egui::{Checkbox, Grid, Id, RichText, TextEdit, Ui},
+    epaint::Color32,
+};
+
+/// Draws the class at several addresses side by side, highlighting fields that differ.
+pub struct CompareView {
+    /// Addresses of other instances, the first column is always the class address.
+    addresses: Vec<String>,
+    only_differences: bool,
+}
+
+impl CompareView {
+    pub fn new() -> Self {
+        Self {
+            addresses: vec![String::new()],
+            only_differences: false,
+        }
+    }
+
+    /// `instances` of the class can be added to the comparison at once.
+    pub fn controls(&mut self, ui: &mut Ui, instances: &[Instance]) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Compare with");
+
+            let mut remove = None;
+            for (i, address) in self.addresses.iter_mut().enumerate() {
+                let r = ui.add(
+                    TextEdit::singleline(address)
+                        .desired_width(160.)
+                        .hint_text("Address"),
+                );
+                if !address.is_empty() && parse_address(address).is_none() {
+                    r.on_hover_text("Address is in invalid format");
+                }
+
+                if ui.small_button("x").clicked() {
+                    remove = Some(i);
+                }
+            }
+
+            if let Some(i) = remove {
+                self.addresses.remove(i);
+            }
+
+            if ui.small_button("+").clicked() {
+                self.addresses.push(String::new());
+            }
+
+            if !instances.is_empty() && ui.button("Add instances").clicked() {
+                self.addresses.retain(|a| !a.is_empty());
+                for instance in instances {
+                    if !self.addresses.contains(&instance.address) {
+                        self.addresses.push(instance.address.clone());
+                    }
+                }
+            }
+
+            ui.add(Checkbox::new(
+                &mut self.only_differences,
+                "Only differences",
+            ));
+        });
+    }
+
+    pub fn draw(
+        &self,
+        ui: &mut Ui,
+        ctx: &mut InspectionContext,
+        class: &Class,
+    ) -> Option<FieldResponse> {
+        let addresses = std::iter::once(ctx.address)
+            .chain(self.addresses.iter().filter_map(|a| parse_address(a)))
+            .collect::<Vec<_>>();
+
+        let (class_list, address, container) = (ctx.class_list, ctx.address, ctx.current_container);
+        // Inherited fields are drawn first, but can only be edited in the base class.
+        let rows = class_list
+            .bases(class.id())
+            .into_iter()
+            .flat_map(|b| b.fields.iter().map(move |f| (b.id(), f, false)))
+            .chain(class.fields.iter().map(|f| (class.id(), f, true)));
+
+        let mut response = None;
+        Grid::new("_compare_view").striped(true).show(ui, |ui| {
+            ui.label("");
+            for address in addresses.iter() {
+                ui.label(RichText::new(format!("{address:X}")).color(Color32::LIGHT_GREEN));
+            }
+            ui.end_row();
+
+            let mut offset = 0;
+            for (container, field, enabled) in rows {
+                let size = field.size();
+                let values = addresses
+                    .iter()
+                    .map(|a| {
+                        let mut buf = vec![0; size];
+                        ctx.process.read(a + offset, &mut buf);
+                        buf
+                    })
+                    .collect::<Vec<_>>();
+
+                let differs = values.windows(2).any(|w| w[0] != w[1]);
+                if self.only_differences && !differs {
+                    offset += size;
+                    continue;
+                }
+
+                ui.label(RichText::new(if differs { "!=" } else { "" }).color(Color32::RED));
+
+                let id = Id::new(ctx.level_rng.u64(..));
+                ctx.current_container = container;
+                for (i, address) in addresses.iter().enumerate() {
+                    (ctx.address, ctx.offset, ctx.current_id) = (*address, offset, id.with(i));
+                    ui.add_enabled_ui(enabled, |ui| {
+                        ui.vertical(|ui| response = response.take().or(field.draw(ui, ctx)));
+                    });
+                }
+                ui.end_row();
+
+                offset += size;
+            }
+        });
+
+        (ctx.address, ctx.offset, ctx.current_container) = (address, 0, container);
+        response
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{state::StateRef, FID_M};
+use eframe::{
+    egui::{Context, DragValue, Grid, RichText, ScrollArea, Window},
+    epaint::{vec2, Color32},
+};
+
+pub struct FreezeListWindow {
+    shown: bool,
+    state: StateRef,
+}
+
+impl FreezeListWindow {
+    pub fn new(state: StateRef) -> Self {
+        Self {
+            shown: false,
+            state,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.shown = !self.shown;
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        if !self.shown {
+            return;
+        }
+
+        let state = &mut *self.state.borrow_mut();
+        Window::new("Frozen values")
+            .open(&mut self.shown)
+            .default_size(vec2(360., 240.))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Interval");
+
+                    let mut interval = state.freezes.interval_ms();
+                    let r = ui.add(
+                        DragValue::new(&mut interval)
+                            .range(1..=10_000)
+                            .suffix(" ms"),
+                    );
+                    if r.changed() {
+                        state.freezes.set_interval_ms(interval);
+                        state.config.freeze_interval_ms = Some(interval);
+                    }
+                    if r.drag_stopped() || r.lost_focus() {
+                        state.config.save();
+                    }
+
+                    if ui.button("Unfreeze all").clicked() {
+                        state.freezes.clear();
+                    }
+                });
+
+                ui.separator();
+
+                let freezes = state.freezes.list();
+                if freezes.is_empty() {
+                    ui.label("Right click a named field to freeze its value.");
+                    return;
+                }
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    Grid::new("_freeze_list").striped(true).show(ui, |ui| {
+                        for freeze in freezes {
+                            ui.label(&freeze.label);
+                            ui.label(
+                                RichText::new(format!("{:X}", freeze.address))
+                                    .font(FID_M)
+                                    .color(Color32::LIGHT_GREEN),
+                            );
+                            ui.label(
+                                RichText::new(
+                                    freeze
+                                        .bytes
+                                        .iter()
+                                        .map(|b| format!("{b:02X}"))
+                                        .collect::<Vec<_>>()
+                                        .join(" "),
+                                )
+                                .font(FID_M),
+                            );
+
+                            if ui.button("Unfreeze").clicked() {
+                                state.freezes.unfreeze(freeze.pid, freeze.address);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+    }
+}
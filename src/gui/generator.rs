@@ -1,4 +1,8 @@
-use crate::{field::CodegenData, generator::AvailableGenerator, state::StateRef};
+use crate::{
+    field::CodegenData,
+    generator::{AvailableGenerator, Layout},
+    state::StateRef,
+};
 use eframe::{
     egui::{ComboBox, Context, FontSelection, TextEdit, Window},
     epaint::FontId,
@@ -54,10 +58,34 @@ impl GeneratorWindow {
                             classes: state.class_list.classes(),
                         };
 
-                        for class in state.class_list.classes() {
-                            gen.begin_class(&class.name);
+                        gen.set_class_namespaces(
+                            state
+                                .class_list
+                                .classes()
+                                .iter()
+                                .map(|c| (c.name.clone(), c.namespace.clone()))
+                                .collect(),
+                        );
+
+                        // Base classes have to be defined before derived ones,
+                        // otherwise classes of the same namespace are kept together.
+                        let mut classes = state.class_list.in_base_order();
+                        classes.sort_by_cached_key(|c| {
+                            (state.class_list.bases(c.id()).len(), c.namespace.clone())
+                        });
+
+                        for class in classes {
+                            gen.enter_namespace(&class.namespace);
+                            gen.set_layout(Layout {
+                                base_size: state.class_list.base_size(class.id()),
+                                size: state.class_list.size_of(class.id()),
+                                alignment: class.alignment,
+                            });
+                            let base = class.base(state.class_list.classes());
+                            gen.set_comment(&class.note);
+                            gen.begin_class(&class.name, base.map(|b| b.name.as_str()));
                             for field in class.fields.iter() {
-                                field.codegen(&mut *gen, &data);
+                                field.codegen_commented(&mut *gen, &data);
                             }
                             gen.end_class();
                         }
@@ -0,0 +1,257 @@
+use crate::{
+    class::ClassId,
+    graph::{ClassGraph, EdgeKind, NODE_SIZE},
+    state::StateRef,
+};
+use eframe::{
+    egui::{Align2, Context, FontId, Sense, Ui, Window},
+    epaint::{vec2, Color32, CornerRadius, Pos2, Rect, Shape, Stroke, StrokeKind, Vec2},
+};
+use std::{collections::HashMap, fs};
+
+/// Window that draws classes as nodes connected by pointers and inheritance.
+pub struct GraphWindow {
+    shown: bool,
+    graph: Option<ClassGraph>,
+    /// Positions of nodes in the graph space, nodes can be dragged around.
+    positions: HashMap<ClassId, (f32, f32)>,
+    /// Node that is being dragged, the view is panned when the background is dragged.
+    dragged: Option<ClassId>,
+    pan: Vec2,
+    zoom: f32,
+    state: StateRef,
+}
+
+impl GraphWindow {
+    pub fn new(state: StateRef) -> Self {
+        Self {
+            shown: false,
+            graph: None,
+            positions: HashMap::new(),
+            dragged: None,
+            pan: vec2(20., 20.),
+            zoom: 1.,
+            state,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.shown = !self.shown;
+        if self.shown {
+            self.rebuild(false);
+        }
+    }
+
+    /// Rebuilds the graph, positions of the nodes are kept unless `relayout` is set.
+    fn rebuild(&mut self, relayout: bool) {
+        let graph = ClassGraph::build(&self.state.borrow().class_list);
+        let layout = graph.layout();
+
+        if relayout {
+            self.positions = layout;
+        } else {
+            self.positions.retain(|id, _| layout.contains_key(id));
+            for (id, pos) in layout {
+                self.positions.entry(id).or_insert(pos);
+            }
+        }
+        self.graph = Some(graph);
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        if !self.shown {
+            return;
+        }
+
+        let mut shown = self.shown;
+        Window::new("Class graph")
+            .open(&mut shown)
+            .default_size(vec2(640., 480.))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Refresh").clicked() {
+                        self.rebuild(false);
+                    }
+                    if ui.button("Layout").clicked() {
+                        self.rebuild(true);
+                    }
+                    if ui.button("Reset view").clicked() {
+                        (self.pan, self.zoom) = (vec2(20., 20.), 1.);
+                    }
+
+                    ui.separator();
+                    if ui.button("Export DOT").clicked() {
+                        self.export("dot");
+                    }
+                    if ui.button("Export SVG").clicked() {
+                        self.export("svg");
+                    }
+                });
+                ui.separator();
+
+                self.canvas(ui);
+            });
+        self.shown = shown;
+    }
+
+    fn canvas(&mut self, ui: &mut Ui) {
+        let Some(graph) = self.graph.as_ref() else {
+            return;
+        };
+
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        let rect = response.rect;
+        painter.rect_filled(rect, 0., Color32::from_gray(16));
+
+        if response.hovered() {
+            let (scroll, zoom) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+            let factor = zoom * (scroll / 400.).exp();
+            if factor != 1. {
+                // Zooms around the cursor.
+                if let Some(cursor) = response.hover_pos() {
+                    let anchor = cursor - rect.min - self.pan;
+                    self.pan -= anchor * (factor - 1.);
+                }
+                self.zoom = (self.zoom * factor).clamp(0.2, 4.);
+            }
+        }
+
+        let (pan, zoom, origin) = (self.pan, self.zoom, rect.min);
+        let to_screen = |(x, y): (f32, f32)| origin + pan + vec2(x, y) * zoom;
+        let node_rect = |id: &ClassId, positions: &HashMap<ClassId, (f32, f32)>| {
+            let pos = positions.get(id).copied().unwrap_or_default();
+            Rect::from_min_size(to_screen(pos), vec2(NODE_SIZE.0, NODE_SIZE.1) * zoom)
+        };
+
+        let node_at = |p: Pos2| {
+            graph
+                .nodes
+                .iter()
+                .rev()
+                .find(|n| node_rect(&n.id, &self.positions).contains(p))
+                .map(|n| n.id)
+        };
+        let hovered = response.hover_pos().and_then(node_at);
+
+        // Dragging a node moves it, dragging the background pans the view.
+        if response.drag_started() {
+            self.dragged = ui.input(|i| i.pointer.press_origin()).and_then(node_at);
+        }
+        if response.dragged() {
+            let delta = response.drag_delta();
+            match self.dragged.and_then(|id| self.positions.get_mut(&id)) {
+                Some(pos) => {
+                    pos.0 += delta.x / zoom;
+                    pos.1 += delta.y / zoom;
+                }
+                None => self.pan += delta,
+            }
+        }
+        if response.drag_stopped() {
+            self.dragged = None;
+        }
+
+        let state = &mut *self.state.borrow_mut();
+        if let Some(id) = hovered.filter(|_| response.clicked()) {
+            *state.class_list.selected_mut() = Some(id);
+        }
+
+        let painter = painter.with_clip_rect(rect);
+        let font = FontId::monospace(12. * zoom);
+        for edge in graph.edges.iter() {
+            let (from, to) = (
+                node_rect(&edge.from, &self.positions),
+                node_rect(&edge.to, &self.positions),
+            );
+            let (start, end) = (from.center_bottom(), to.center_top());
+            let color = match edge.kind {
+                EdgeKind::Pointer => Color32::LIGHT_GRAY,
+                EdgeKind::Inheritance => Color32::LIGHT_BLUE,
+            };
+
+            painter.line_segment([start, end], Stroke::new(1., color));
+            arrow_head(&painter, start, end, 8. * zoom, color);
+            painter.text(
+                start + (end - start) / 2.,
+                Align2::LEFT_BOTTOM,
+                edge.label(),
+                font.clone(),
+                Color32::YELLOW,
+            );
+        }
+
+        let selected = state.class_list.selected();
+        for node in graph.nodes.iter() {
+            let r = node_rect(&node.id, &self.positions);
+            let stroke = if selected == Some(node.id) {
+                Stroke::new(2., Color32::LIGHT_GREEN)
+            } else if hovered == Some(node.id) {
+                Stroke::new(1.5, Color32::WHITE)
+            } else {
+                Stroke::new(1., Color32::GRAY)
+            };
+
+            painter.rect(
+                r,
+                CornerRadius::same(4),
+                Color32::from_gray(40),
+                stroke,
+                StrokeKind::Inside,
+            );
+            painter.text(
+                r.center_top() + vec2(0., 4. * zoom),
+                Align2::CENTER_TOP,
+                &node.name,
+                font.clone(),
+                Color32::WHITE,
+            );
+            painter.text(
+                r.center_bottom() - vec2(0., 4. * zoom),
+                Align2::CENTER_BOTTOM,
+                format!("{:#X} bytes", node.size),
+                font.clone(),
+                Color32::GRAY,
+            );
+        }
+    }
+
+    fn export(&mut self, extension: &str) {
+        let Some(graph) = self.graph.as_ref() else {
+            return;
+        };
+
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Export class graph")
+            .add_filter(&extension.to_uppercase(), &[extension])
+            .save_file()
+        else {
+            return;
+        };
+
+        let out = match extension {
+            "svg" => graph.to_svg(&self.positions),
+            _ => graph.to_dot(),
+        };
+
+        let state = &mut *self.state.borrow_mut();
+        match fs::write(path, out) {
+            Ok(_) => _ = state.toasts.info("Class graph was exported"),
+            Err(e) => {
+                _ = state
+                    .toasts
+                    .error(format!("Failed to export the graph. {e}"))
+            }
+        }
+    }
+}
+
+fn arrow_head(painter: &eframe::egui::Painter, start: Pos2, end: Pos2, size: f32, color: Color32) {
+    let dir = (end - start).normalized();
+    let normal = vec2(-dir.y, dir.x);
+    let base = end - dir * size;
+    painter.add(Shape::convex_polygon(
+        vec![end, base + normal * size / 2., base - normal * size / 2.],
+        color,
+        Stroke::NONE,
+    ));
+}
@@ -0,0 +1,285 @@
+use crate::{
+    address::parse_address,
+    class::{Class, ClassId},
+    field::{FieldId, FieldKind},
+    guess,
+    state::{GlobalState, StateRef},
+};
+use eframe::{
+    egui::{Context, DragValue, ProgressBar, TextEdit, Ui, Window},
+    epaint::vec2,
+};
+use std::time::{Duration, Instant};
+
+struct Sampling {
+    addresses: Vec<usize>,
+    /// Ids, offsets and sizes of own fields when sampling started,
+    /// sampling is aborted if the class is edited in the meantime.
+    layout: Vec<(FieldId, usize, usize)>,
+    /// Own bytes of the class, one per instance for every sample.
+    snapshots: Vec<Vec<u8>>,
+    remaining: usize,
+    next: Instant,
+}
+
+/// Window that guesses kinds of unknown fields of the class.
+pub struct GuessWindow {
+    class_id: Option<ClassId>,
+    /// Addresses of other instances of the class, one per line.
+    addresses: String,
+    samples: usize,
+    interval_ms: u64,
+    sampling: Option<Sampling>,
+    state: StateRef,
+}
+
+impl GuessWindow {
+    pub fn new(state: StateRef) -> Self {
+        Self {
+            class_id: None,
+            addresses: String::new(),
+            samples: 1,
+            interval_ms: 100,
+            sampling: None,
+            state,
+        }
+    }
+
+    pub fn open(&mut self, class_id: ClassId) {
+        if self.class_id != Some(class_id) {
+            self.addresses.clear();
+        }
+
+        self.class_id = Some(class_id);
+        self.sampling = None;
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        let Some(class_id) = self.class_id else {
+            return;
+        };
+
+        let mut open = true;
+        Window::new("Guess types")
+            .open(&mut open)
+            .default_size(vec2(320., 200.))
+            .show(ctx, |ui| self.ui(ui, class_id));
+
+        if !open || self.state.borrow().class_list.by_id(class_id).is_none() {
+            self.class_id = None;
+            self.sampling = None;
+            return;
+        }
+
+        self.sample(ctx, class_id);
+    }
+
+    fn ui(&mut self, ui: &mut Ui, class_id: ClassId) {
+        let state = &mut *self.state.borrow_mut();
+        let Some(class) = state.class_list.by_id(class_id) else {
+            return;
+        };
+
+        ui.label(format!("Class: {}", class.name));
+        ui.horizontal(|ui| {
+            ui.label("Other instances");
+            if !class.instances.is_empty() && ui.button("Add named instances").clicked() {
+                let current = class.address.get();
+                for instance in class.instances.iter() {
+                    if instance.resolve() != Some(current)
+                        && !self.addresses.lines().any(|l| l.trim() == instance.address)
+                    {
+                        if !self.addresses.is_empty() && !self.addresses.ends_with('\n') {
+                            self.addresses.push('\n');
+                        }
+                        self.addresses += &instance.address;
+                    }
+                }
+            }
+        });
+        ui.add(
+            TextEdit::multiline(&mut self.addresses)
+                .desired_rows(3)
+                .hint_text("One address per line"),
+        );
+
+        ui.horizontal(|ui| {
+            ui.label("Samples");
+            ui.add(DragValue::new(&mut self.samples).range(1..=100));
+
+            ui.add_enabled_ui(self.samples > 1, |ui| {
+                ui.label("Interval");
+                ui.add(
+                    DragValue::new(&mut self.interval_ms)
+                        .range(1..=10_000)
+                        .suffix(" ms"),
+                );
+            });
+        });
+
+        ui.separator();
+
+        if let Some(sampling) = self.sampling.as_ref() {
+            let done = self.samples - sampling.remaining;
+            ui.add(ProgressBar::new(done as f32 / self.samples as f32).text("Sampling..."));
+            return;
+        }
+
+        let guessed = class
+            .fields
+            .iter()
+            .filter(|f| state.guesses.contains_key(&f.id()))
+            .map(|f| f.id())
+            .collect::<Vec<_>>();
+
+        ui.horizontal(|ui| {
+            if ui.button("Guess").clicked() {
+                self.start(state, class_id);
+            }
+
+            ui.add_enabled_ui(!guessed.is_empty(), |ui| {
+                if ui.button("Accept all").clicked() {
+                    if let Some(inverse) = guess::accept(
+                        &mut state.class_list,
+                        &mut state.guesses,
+                        class_id,
+                        &guessed,
+                    ) {
+                        state.history.push(inverse);
+                        state.selection = None;
+                        state.dummy = false;
+                    }
+                }
+
+                if ui.button("Dismiss all").clicked() {
+                    guessed.iter().for_each(|id| _ = state.guesses.remove(id));
+                }
+            });
+        });
+
+        ui.label(format!(
+            "{} guesses, click one to accept it.",
+            guessed.len()
+        ));
+    }
+
+    fn start(&mut self, state: &mut GlobalState, class_id: ClassId) {
+        if state.process.read().is_none() {
+            state.toasts.error("Attach to a process first");
+            return;
+        }
+
+        let class = state.class_list.by_id(class_id).unwrap();
+        let layout = layout(class);
+        let mut addresses = vec![class.address.get()];
+        for line in self
+            .addresses
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+        {
+            match parse_address(line) {
+                Some(address) => addresses.push(address),
+                None => {
+                    state.toasts.error(format!("Invalid address: {line}"));
+                    return;
+                }
+            }
+        }
+
+        self.sampling = Some(Sampling {
+            addresses,
+            layout,
+            snapshots: vec![],
+            remaining: self.samples,
+            next: Instant::now(),
+        });
+    }
+
+    /// Takes the next sample once it is due and makes guesses after the last one.
+    fn sample(&mut self, ctx: &Context, class_id: ClassId) {
+        let Some(sampling) = self.sampling.as_mut() else {
+            return;
+        };
+
+        let now = Instant::now();
+        if now < sampling.next {
+            ctx.request_repaint_after(sampling.next - now);
+            return;
+        }
+
+        let state = &mut *self.state.borrow_mut();
+        let process_lock = state.process.read();
+        let Some(process) = process_lock.as_ref() else {
+            self.sampling = None;
+            return;
+        };
+        let class = state.class_list.by_id(class_id).unwrap();
+        if layout(class) != sampling.layout {
+            drop(process_lock);
+            state
+                .toasts
+                .error("Class was edited while sampling, guessing aborted");
+            self.sampling = None;
+            return;
+        }
+
+        let start = state.class_list.base_size(class_id);
+        let size = sampling.layout.last().map_or(0, |(_, o, s)| o + s);
+        for address in sampling.addresses.iter() {
+            let mut buf = vec![0; size];
+            process.read(address + start, &mut buf);
+            sampling.snapshots.push(buf);
+        }
+
+        sampling.remaining -= 1;
+        if sampling.remaining > 0 {
+            let interval = Duration::from_millis(self.interval_ms);
+            sampling.next = now + interval;
+            ctx.request_repaint_after(interval);
+            return;
+        }
+
+        let mut count = 0;
+        for (field, &(_, offset, size)) in class.fields.iter().zip(sampling.layout.iter()) {
+            let unknown = matches!(
+                field.kind(),
+                FieldKind::Unk8 | FieldKind::Unk16 | FieldKind::Unk32 | FieldKind::Unk64
+            );
+
+            if unknown {
+                let samples = sampling
+                    .snapshots
+                    .iter()
+                    .map(|s| s.get(offset..offset + size))
+                    .collect::<Option<Vec<_>>>();
+
+                match samples.and_then(|samples| guess::infer(process, size, &samples)) {
+                    Some(guess) => {
+                        state.guesses.insert(field.id(), guess);
+                        count += 1;
+                    }
+                    None => _ = state.guesses.remove(&field.id()),
+                }
+            }
+        }
+
+        drop(process_lock);
+        state.toasts.info(format!("Guessed {count} fields"));
+        self.sampling = None;
+    }
+}
+
+/// Ids, offsets and sizes of own fields of the class.
+fn layout(class: &Class) -> Vec<(FieldId, usize, usize)> {
+    let mut offset = 0;
+    class
+        .fields
+        .iter()
+        .map(|f| {
+            let field = (f.id(), offset, f.size());
+            offset += f.size();
+            field
+        })
+        .collect()
+}
@@ -1,17 +1,42 @@
+use super::{CompareView, MemoryViewWindow};
 use crate::{
-    address::parse_address, context::InspectionContext, field::FieldResponse, state::StateRef,
+    address::parse_address,
+    class::{ClassId, Instance, LayoutIssue},
+    context::InspectionContext,
+    field::{allocate_padding, FieldResponse},
+    guess,
+    history::Command,
+    rtti,
+    state::{GlobalState, StateRef},
+    watch::WatchPath,
     FID_M,
 };
 use eframe::{
-    egui::{collapsing_header::CollapsingState, CentralPanel, Context, Id, ScrollArea, Ui},
-    epaint::FontId,
+    egui::{
+        collapsing_header::CollapsingState, CentralPanel, ComboBox, Context, Id, RichText,
+        ScrollArea, TextEdit, Ui,
+    },
+    epaint::{Color32, FontId},
 };
 use fastrand::Rng;
+use std::time::{Duration, Instant};
+
+/// How long the type information of the inspected object is reused before it's read again.
+const RTTI_TTL: Duration = Duration::from_secs(1);
 
 pub struct InspectorPanel {
     address_buffer: String,
+    /// Label of the instance that will be saved.
+    instance_label: String,
+    /// Declared size of the class being edited.
+    layout_size: String,
     state: StateRef,
     allow_scroll: bool,
+    memory_view: MemoryViewWindow,
+    /// Compare mode draws the class at several addresses at once.
+    compare: Option<CompareView>,
+    /// Description of the type at the address and when it was resolved.
+    rtti: Option<(usize, Instant, Option<String>)>,
 }
 
 impl InspectorPanel {
@@ -19,7 +44,12 @@ impl InspectorPanel {
         Self {
             state,
             allow_scroll: true,
+            memory_view: MemoryViewWindow::new(state),
+            compare: None,
+            rtti: None,
             address_buffer: format!("0x{:X}", 0),
+            instance_label: String::new(),
+            layout_size: String::new(),
         }
     }
 
@@ -28,6 +58,8 @@ impl InspectorPanel {
             ui.scope(|ui| {
                 ui.style_mut().override_font_id = Some(FontId::monospace(16.));
 
+                self.tab_bar(ui);
+
                 {
                     let state = self.state.borrow();
                     if state.process.read().is_none() {
@@ -37,7 +69,9 @@ impl InspectorPanel {
                         return;
                     }
 
-                    if state.class_list.selected_class().is_none() {
+                    if state.inspector.active.is_none()
+                        && state.class_list.selected_class().is_none()
+                    {
                         ui.centered_and_justified(|ui| {
                             ui.heading("Select a class from the class list to begin inspection.");
                         });
@@ -48,28 +82,81 @@ impl InspectorPanel {
                 CollapsingState::load_with_default_open(ctx, Id::new("_inspector_panel"), true)
                     .show_header(ui, |ui| {
                         let state = &mut *self.state.borrow_mut();
-                        let active_class = state.class_list.selected_class()?;
+                        let (active_class, address) = state.inspector.target(&state.class_list)?;
 
-                        ui.label(format!("{} - ", active_class.name));
-                        ui.spacing_mut().text_edit_width = self
-                            .address_buffer
+                        match active_class.base(state.class_list.classes()) {
+                            Some(base) => {
+                                ui.label(format!("{} : {} - ", active_class.name, base.name))
+                            }
+                            None => ui.label(format!("{} - ", active_class.name)),
+                        };
+
+                        let buffer = &mut self.address_buffer;
+                        ui.spacing_mut().text_edit_width = buffer
                             .chars()
                             .map(|c| ui.fonts(|f| f.glyph_width(&FID_M, c)))
                             .sum::<f32>()
                             .max(160.);
-                        let selected_class = state.class_list.selected_class().unwrap();
 
-                        let r = ui.text_edit_singleline(&mut self.address_buffer);
+                        let r = ui.text_edit_singleline(buffer);
                         if r.lost_focus() {
-                            if let Some(addr) = parse_address(&self.address_buffer) {
-                                selected_class.address.set(addr);
-                            } else {
-                                state.toasts.error("Address is in invalid format");
+                            match parse_address(buffer) {
+                                Some(addr) => match state.inspector.active {
+                                    Some(i) => state.inspector.tabs[i].address = addr,
+                                    None => active_class.address.set(addr),
+                                },
+                                None => _ = state.toasts.error("Address is in invalid format"),
                             }
                         }
 
+                        // Invalid addresses are discarded, the last valid one is kept.
                         if !r.has_focus() {
-                            self.address_buffer = format!("0x{:X}", selected_class.address.get());
+                            let current = match state.inspector.active {
+                                Some(i) => state.inspector.tabs[i].address,
+                                None => active_class.address.get(),
+                            };
+                            *buffer = format!("0x{current:X}");
+                        }
+
+                        let class_id = active_class.id();
+                        self.instances_ui(ui, state, class_id, address);
+
+                        if self
+                            .rtti
+                            .as_ref()
+                            .is_none_or(|(a, t, _)| *a != address || t.elapsed() > RTTI_TTL)
+                        {
+                            let info = state
+                                .process
+                                .read()
+                                .as_ref()
+                                .and_then(|p| rtti::resolve(p, address));
+                            self.rtti = Some((address, Instant::now(), info.map(|i| i.describe())));
+                        }
+                        if let Some((_, _, Some(description))) = &self.rtti {
+                            ui.label(RichText::new(description).color(Color32::GRAY))
+                                .on_hover_text("Run-time type information");
+                        }
+
+                        if ui
+                            .selectable_label(self.compare.is_some(), "Compare")
+                            .on_hover_text("Compare instances of the class side by side")
+                            .clicked()
+                        {
+                            self.compare = match self.compare {
+                                Some(_) => None,
+                                None => Some(CompareView::new()),
+                            };
+                        }
+
+                        ui.menu_button("Layout", |ui| self.layout_ui(ui, state, class_id));
+
+                        if ui
+                            .button("Open in new tab")
+                            .on_hover_text("Keep inspecting this object in its own tab")
+                            .clicked()
+                        {
+                            state.inspector.open(class_id, address);
                         }
 
                         Some(())
@@ -78,52 +165,326 @@ impl InspectorPanel {
             });
         });
 
+        self.memory_view.show(ctx);
         None
     }
 
+    fn tab_bar(&mut self, ui: &mut Ui) {
+        let state = &mut *self.state.borrow_mut();
+        let GlobalState {
+            inspector,
+            class_list,
+            ..
+        } = state;
+        if inspector.tabs.is_empty() {
+            return;
+        }
+
+        // Tabs of deleted classes are closed.
+        inspector
+            .tabs
+            .retain(|t| class_list.by_id(t.class_id).is_some());
+        if inspector.active.is_some_and(|i| i >= inspector.tabs.len()) {
+            inspector.active = None;
+        }
+
+        let mut close = None;
+        ui.horizontal_wrapped(|ui| {
+            if ui
+                .selectable_label(inspector.active.is_none(), "Selected class")
+                .clicked()
+            {
+                inspector.active = None;
+            }
+
+            for (i, tab) in inspector.tabs.iter().enumerate() {
+                let name = &class_list.by_id(tab.class_id).unwrap().name;
+                let text = format!("{name} @ 0x{:X}", tab.address);
+                if ui
+                    .selectable_label(inspector.active == Some(i), text)
+                    .clicked()
+                {
+                    inspector.active = Some(i);
+                }
+                if ui.small_button("x").clicked() {
+                    close = Some(i);
+                }
+            }
+        });
+        ui.separator();
+
+        if let Some(i) = close {
+            inspector.tabs.remove(i);
+            inspector.active = match inspector.active {
+                Some(a) if a == i => None,
+                Some(a) if a > i => Some(a - 1),
+                other => other,
+            };
+        }
+    }
+
+    /// Dropdown to switch between named instances of the class
+    /// and to save the current address as a new one.
+    fn instances_ui(
+        &mut self,
+        ui: &mut Ui,
+        state: &mut GlobalState,
+        class_id: ClassId,
+        address: usize,
+    ) {
+        let Some(class) = state.class_list.by_id_mut(class_id) else {
+            return;
+        };
+
+        let current = class
+            .instances
+            .iter()
+            .position(|i| i.resolve() == Some(address));
+        let (mut switch, mut remove, mut save) = (None, None, false);
+        ComboBox::from_id_salt("_inspector_instances")
+            .selected_text(current.map_or("Instances", |i| class.instances[i].label.as_str()))
+            .show_ui(ui, |ui| {
+                for (i, instance) in class.instances.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .selectable_label(current == Some(i), &instance.label)
+                            .on_hover_text(&instance.address)
+                            .clicked()
+                        {
+                            switch = Some(instance.address.clone());
+                        }
+                        if ui.small_button("x").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                }
+
+                if !class.instances.is_empty() {
+                    ui.separator();
+                }
+                ui.horizontal(|ui| {
+                    ui.add(
+                        TextEdit::singleline(&mut self.instance_label)
+                            .desired_width(120.)
+                            .hint_text("Label"),
+                    );
+                    save = ui
+                        .button("Save")
+                        .on_hover_text("Save the current address as an instance")
+                        .clicked();
+                });
+            });
+
+        if let Some(expr) = switch {
+            match (parse_address(&expr), state.inspector.active) {
+                (Some(addr), Some(i)) => state.inspector.tabs[i].address = addr,
+                (Some(addr), None) => class.address.set(addr),
+                (None, _) => _ = state.toasts.error("Address is in invalid format"),
+            }
+        }
+
+        if let Some(i) = remove {
+            class.instances.remove(i);
+            state.dummy = false;
+        }
+
+        if save {
+            let label = self.instance_label.trim();
+            if label.is_empty() {
+                state.toasts.error("Instance label can't be empty");
+            } else if class.instances.iter().any(|i| i.label == label) {
+                state
+                    .toasts
+                    .error("Instance with this label already exists");
+            } else {
+                class.instances.push(Instance {
+                    label: label.to_owned(),
+                    address: format!("0x{address:X}"),
+                });
+                self.instance_label.clear();
+                state.dummy = false;
+            }
+        }
+    }
+
+    /// Declared size and alignment of the class.
+    fn layout_ui(&mut self, ui: &mut Ui, state: &mut GlobalState, class_id: ClassId) {
+        let Some(class) = state.class_list.by_id(class_id) else {
+            return;
+        };
+        let (size, alignment) = (class.size, class.alignment);
+        let mut layout = None;
+
+        ui.horizontal(|ui| {
+            ui.label("Size");
+            let r = ui.add(
+                TextEdit::singleline(&mut self.layout_size)
+                    .desired_width(100.)
+                    .hint_text(format!("0x{:X}", state.class_list.size_of(class_id))),
+            );
+
+            if r.lost_focus() {
+                match self.layout_size.trim() {
+                    "" => layout = Some((None, alignment)),
+                    text => match parse_address(text) {
+                        Some(size) => layout = Some((Some(size), alignment)),
+                        None => _ = state.toasts.error("Size is in invalid format"),
+                    },
+                }
+            } else if !r.has_focus() {
+                self.layout_size = size.map(|s| format!("0x{s:X}")).unwrap_or_default();
+            }
+        })
+        .response
+        .on_hover_text("Leave empty to use the size of the fields");
+
+        let natural = format!("Natural ({})", state.class_list.alignment_of(class_id));
+        ComboBox::new("_inspector_alignment", "Alignment")
+            .selected_text(alignment.map_or(natural.clone(), |a| a.to_string()))
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(alignment.is_none(), natural).clicked() {
+                    layout = Some((size, None));
+                }
+
+                for a in [1, 2, 4, 8, 16, 32, 64] {
+                    if ui
+                        .selectable_label(alignment == Some(a), a.to_string())
+                        .clicked()
+                    {
+                        layout = Some((size, Some(a)));
+                    }
+                }
+            });
+
+        let missing = state
+            .class_list
+            .layout_issues(class_id)
+            .into_iter()
+            .find_map(|issue| match issue {
+                LayoutIssue::Undersized { used, size } => Some(size - used),
+                _ => None,
+            });
+        if let Some(missing) = missing {
+            if ui.button("Pad to the declared size").clicked() {
+                let at = class.fields.len();
+                state.history.apply(
+                    Command::Splice {
+                        class_id,
+                        at,
+                        len: 0,
+                        fields: allocate_padding(missing),
+                    },
+                    &mut state.class_list,
+                    None,
+                );
+                state.dummy = false;
+            }
+        }
+
+        if let Some((size, alignment)) = layout.filter(|l| *l != (size, alignment)) {
+            state.history.apply(
+                Command::SetLayout {
+                    class_id,
+                    size,
+                    alignment,
+                },
+                &mut state.class_list,
+                None,
+            );
+            state.dummy = false;
+        }
+    }
+
     fn inspect(&mut self, ui: &mut Ui) -> Option<()> {
         let state = &mut *self.state.borrow_mut();
-        let rng = Rng::with_seed(0);
+        state.changes.prune();
+        let rng = Rng::with_seed(state.inspector.seed());
+
+        let (class, address) = state.inspector.target(&state.class_list)?;
+        for issue in state.class_list.layout_issues(class.id()) {
+            ui.label(RichText::new(issue.to_string()).color(Color32::YELLOW));
+        }
 
         let process_lock = state.process.read();
         let mut ctx = InspectionContext {
-            address: state.class_list.selected_class()?.address.get(),
-            current_container: state.class_list.selected()?,
+            address,
+            current_container: class.id(),
             process: process_lock.as_ref()?,
             class_list: &state.class_list,
-            selection: state.selection,
             toasts: &mut state.toasts,
+            history: &mut state.history,
+            freezes: &state.freezes,
+            guesses: &state.guesses,
+            changes: &state.changes,
+            watches: &state.watches,
+            dummy: &mut state.dummy,
             current_id: Id::new(0),
             parent_id: Id::new(0),
             level_rng: &rng,
             offset: 0,
+            path: WatchPath {
+                address: state.inspector.active.map(|_| address),
+                ..WatchPath::new(class.id())
+            },
+            modifiers: ui.input(|i| i.modifiers),
+            // Taken last, so it isn't lost if the inspection can't be started.
+            selection: state.selection.take(),
         };
 
-        let class = state.class_list.selected_class()?;
+        if let Some(compare) = self.compare.as_mut() {
+            compare.controls(ui, &class.instances);
+            ui.separator();
+        }
 
         let mut new_class = None;
-        #[allow(clippy::single_match)]
-        ScrollArea::vertical()
+        let mut guess = None;
+        let mut tab = None;
+        let response = ScrollArea::vertical()
             .auto_shrink([false, true])
             .hscroll(true)
             .enable_scrolling(self.allow_scroll)
-            .show(ui, |ui| {
-                match class.fields.iter().fold(None, |r, f| {
-                    ctx.current_id = Id::new(rng.u64(..));
-                    r.or(f.draw(ui, &mut ctx))
-                }) {
-                    Some(FieldResponse::NewClass(name, id)) => new_class = Some((name, id)),
-                    Some(FieldResponse::LockScroll) => self.allow_scroll = false,
-                    Some(FieldResponse::UnlockScroll) => self.allow_scroll = true,
-                    None => {}
-                }
-            });
+            .show(ui, |ui| match self.compare.as_ref() {
+                Some(compare) => compare.draw(ui, &mut ctx, class),
+                None => ctx.draw_class(ui, class),
+            })
+            .inner;
+
+        match response {
+            Some(FieldResponse::NewClass(name, id)) => new_class = Some((name, id)),
+            Some(FieldResponse::LockScroll) => self.allow_scroll = false,
+            Some(FieldResponse::UnlockScroll) => self.allow_scroll = true,
+            Some(FieldResponse::ViewMemory(address)) => self.memory_view.open(address),
+            Some(FieldResponse::AcceptGuess(id)) => guess = Some((id, true)),
+            Some(FieldResponse::DismissGuess(id)) => guess = Some((id, false)),
+            Some(FieldResponse::OpenTab(class_id, address)) => tab = Some((class_id, address)),
+            None => {}
+        }
         state.selection = ctx.selection;
 
         if let Some((name, id)) = new_class {
             state.class_list.add_class_with_id(name, id);
         }
 
+        if let Some((class_id, address)) = tab {
+            state.inspector.open(class_id, address);
+        }
+
+        drop(process_lock);
+        match guess {
+            Some((id, true)) => {
+                let class_id = state.class_list.by_field(id).map(|c| c.id())?;
+                if let Some(inverse) =
+                    guess::accept(&mut state.class_list, &mut state.guesses, class_id, &[id])
+                {
+                    state.history.push(inverse);
+                    state.selection = None;
+                    state.dummy = false;
+                }
+            }
+            Some((id, false)) => _ = state.guesses.remove(&id),
+            None => {}
+        }
+
         Some(())
     }
 }
@@ -58,13 +58,20 @@ impl MemflowAttachWindow {
                     ui.separator();
                     ui.add_space(4.);
 
+                    let project = self.state.borrow().target.connector.clone();
                     ScrollArea::vertical().show(ui, |ui| {
                         for ce in self.connectors.iter().filter(|ce| {
                             self.filter.is_empty()
                                 || ce.to_lowercase().contains(&self.filter.to_lowercase())
                         }) {
+                            // Connector the project was saved with.
+                            let text = if project.as_ref() == Some(ce) {
+                                format!("{ce} (project)")
+                            } else {
+                                ce.clone()
+                            };
                             if ui
-                                .button(RichText::new(ce).font(FontId::proportional(16.)))
+                                .button(RichText::new(text).font(FontId::proportional(16.)))
                                 .clicked()
                             {
                                 log::info!("attaching to {ce}");
@@ -78,6 +85,7 @@ impl MemflowAttachWindow {
                                     .os("win32")
                                     .build() {
                                         Ok(new_os) => {
+                                            state.set_connector(ce.clone());
                                             state.toasts.success(format!("Connected to {ce}"));
                                             Some(new_os)
                                         },
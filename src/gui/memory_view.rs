@@ -0,0 +1,109 @@
+use crate::{address::parse_address, state::StateRef, FID_M};
+use eframe::{
+    egui::{Context, RichText, ScrollArea, TextEdit, Window},
+    epaint::{vec2, Color32},
+};
+
+/// Amount of bytes displayed in a single row.
+const ROW_LEN: usize = 16;
+/// Amount of rows displayed at once.
+const ROWS: usize = 32;
+
+/// Window displaying raw memory, used to look at code behind function pointers.
+pub struct MemoryViewWindow {
+    address: Option<usize>,
+    address_buffer: String,
+    state: StateRef,
+}
+
+impl MemoryViewWindow {
+    pub fn new(state: StateRef) -> Self {
+        Self {
+            address: None,
+            address_buffer: String::new(),
+            state,
+        }
+    }
+
+    pub fn open(&mut self, address: usize) {
+        self.address = Some(address);
+        self.address_buffer = format!("{address:X}");
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        let Some(address) = self.address else {
+            return;
+        };
+
+        let state = &mut *self.state.borrow_mut();
+        let process_lock = state.process.read();
+        let Some(process) = process_lock.as_ref() else {
+            return;
+        };
+
+        let mut open = true;
+        Window::new("Memory view")
+            .open(&mut open)
+            .default_size(vec2(560., 480.))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let r = ui.add(TextEdit::singleline(&mut self.address_buffer).font(FID_M));
+                    if r.lost_focus() {
+                        match parse_address(&self.address_buffer) {
+                            Some(new) => self.address = Some(new),
+                            None => _ = state.toasts.error("Address is in invalid format"),
+                        }
+                    }
+
+                    let location = process
+                        .locate(address)
+                        .map(|l| l.describe())
+                        .unwrap_or_else(|| "<unknown>".into());
+                    ui.label(RichText::new(location).color(Color32::GRAY));
+
+                    if !process.is_executable(address) {
+                        ui.label(RichText::new("not executable").color(Color32::RED));
+                    }
+                });
+
+                ui.separator();
+
+                let mut buf = [0; ROW_LEN * ROWS];
+                process.read(address, &mut buf);
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    for (i, row) in buf.chunks(ROW_LEN).enumerate() {
+                        let bytes = row
+                            .iter()
+                            .map(|b| format!("{b:02X}"))
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        let ascii = row
+                            .iter()
+                            .map(|b| {
+                                if b.is_ascii_graphic() {
+                                    *b as char
+                                } else {
+                                    '.'
+                                }
+                            })
+                            .collect::<String>();
+
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new(format!("{:X}", address + i * ROW_LEN))
+                                    .font(FID_M)
+                                    .color(Color32::LIGHT_GREEN),
+                            );
+                            ui.label(RichText::new(bytes).font(FID_M));
+                            ui.label(RichText::new(ascii).font(FID_M).color(Color32::GRAY));
+                        });
+                    }
+                });
+            });
+
+        if !open {
+            self.address = None;
+        }
+    }
+}
@@ -10,10 +10,26 @@ mod memflow_attach;
 pub use memflow_attach::*;
 mod inspector;
 pub use inspector::*;
+mod memory_view;
+pub use memory_view::*;
+mod compare;
+pub use compare::*;
+mod freeze_list;
+pub use freeze_list::*;
 mod generator;
 pub use generator::*;
+mod graph;
+pub use graph::*;
+mod guess;
+pub use guess::*;
+mod references;
+pub use references::*;
 mod spider;
 pub use spider::*;
+mod value_history;
+pub use value_history::*;
+mod watch_list;
+pub use watch_list::*;
 mod utils;
 pub use utils::*;
 mod logger;
@@ -0,0 +1,202 @@
+use crate::{
+    class::{ClassId, Reference},
+    history::Command,
+    state::{GlobalState, StateRef},
+};
+use eframe::{
+    egui::{Button, ComboBox, Context, Id, RichText, ScrollArea, Ui, Window},
+    epaint::{vec2, Color32},
+};
+
+/// Window that lists fields referencing the class. Before a referenced class is deleted
+/// it asks whether references should be retargeted to another class or cleared.
+pub struct ReferencesWindow {
+    class_id: Option<ClassId>,
+    deleting: bool,
+    /// Class references are retargeted to when the class is deleted.
+    retarget: Option<ClassId>,
+    state: StateRef,
+}
+
+impl ReferencesWindow {
+    pub fn new(state: StateRef) -> Self {
+        Self {
+            class_id: None,
+            deleting: false,
+            retarget: None,
+            state,
+        }
+    }
+
+    pub fn open(&mut self, class_id: ClassId) {
+        self.class_id = Some(class_id);
+        self.deleting = false;
+        self.retarget = None;
+    }
+
+    /// Opens the window to decide what to do with references of the class being deleted.
+    pub fn delete(&mut self, class_id: ClassId) {
+        self.open(class_id);
+        self.deleting = true;
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        let Some(class_id) = self.class_id else {
+            return;
+        };
+
+        let mut open = true;
+        let title = if self.deleting {
+            "Delete class"
+        } else {
+            "References"
+        };
+        Window::new(title)
+            .id(Id::new("_references_window"))
+            .open(&mut open)
+            .default_size(vec2(320., 240.))
+            .show(ctx, |ui| self.ui(ui, class_id));
+
+        if !open || self.state.borrow().class_list.by_id(class_id).is_none() {
+            self.class_id = None;
+        }
+    }
+
+    fn ui(&mut self, ui: &mut Ui, class_id: ClassId) {
+        let state = &mut *self.state.borrow_mut();
+        let Some(class) = state.class_list.by_id(class_id) else {
+            return;
+        };
+
+        let references = state.class_list.references(class_id);
+        ui.label(format!(
+            "{} is referenced {} times",
+            class.name,
+            references.len()
+        ));
+        ui.separator();
+
+        let mut jump = None;
+        ScrollArea::vertical().max_height(200.).show(ui, |ui| {
+            for reference in references.iter() {
+                let Some(text) = describe(state, reference) else {
+                    continue;
+                };
+
+                if ui.add(Button::new(text).frame(false)).clicked() {
+                    jump = Some(*reference);
+                }
+            }
+        });
+
+        if let Some(reference) = jump {
+            state.select_field(reference.class_id, reference.field_id);
+        }
+
+        if !self.deleting {
+            return;
+        }
+
+        ui.separator();
+        ui.label(
+            RichText::new("References would point to a missing class after the deletion.")
+                .color(Color32::YELLOW),
+        );
+
+        ui.horizontal(|ui| {
+            let selected = self
+                .retarget
+                .and_then(|id| state.class_list.by_id(id))
+                .map_or("None", |c| c.name.as_str());
+            ComboBox::new("_references_retarget", "Retarget to")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for other in state.class_list.classes() {
+                        if other.id() != class_id
+                            && ui
+                                .selectable_label(self.retarget == Some(other.id()), &other.name)
+                                .clicked()
+                        {
+                            self.retarget = Some(other.id());
+                        }
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            let mut done = false;
+            if ui
+                .add_enabled(self.retarget.is_some(), Button::new("Retarget and delete"))
+                .clicked()
+            {
+                delete(state, class_id, &references, self.retarget);
+                done = true;
+            }
+
+            if ui
+                .button("Clear and delete")
+                .on_hover_text("Pointers will point to a new unknown class")
+                .clicked()
+            {
+                delete(state, class_id, &references, None);
+                done = true;
+            }
+
+            if ui.button("Cancel").clicked() || done {
+                self.class_id = None;
+            }
+        });
+    }
+}
+
+/// Describes the reference, e.g. `Player.weapon`.
+fn describe(state: &GlobalState, reference: &Reference) -> Option<String> {
+    let class = state.class_list.by_id(reference.class_id)?;
+    Some(match reference.field_id {
+        Some(id) => {
+            let field = class.fields.iter().find(|f| f.id() == id)?;
+            format!("{}.{}", class.name, field.name().unwrap_or_default())
+        }
+        None => format!("{} (derived class)", class.name),
+    })
+}
+
+/// Deletes the class, references are retargeted to `to` or cleared if it is `None`.
+fn delete(
+    state: &mut GlobalState,
+    class_id: ClassId,
+    references: &[Reference],
+    to: Option<ClassId>,
+) {
+    // Cleared pointers point to an unknown class, just like new ones.
+    let target = to.unwrap_or_else(|| fastrand::usize(..));
+
+    let mut commands = references
+        .iter()
+        .map(|r| match r.field_id {
+            Some(field_id) => Command::Retarget {
+                class_id: r.class_id,
+                field_id,
+                from: class_id,
+                to: target,
+            },
+            None => Command::SetBase {
+                class_id: r.class_id,
+                base: to.filter(|to| state.class_list.can_set_base(r.class_id, *to)),
+            },
+        })
+        .collect::<Vec<_>>();
+    commands.push(Command::RemoveClass(class_id));
+
+    if state
+        .selection
+        .as_ref()
+        .is_some_and(|s| s.container_id == class_id)
+    {
+        state.selection = None;
+    }
+    state
+        .history
+        .apply(Command::Batch(commands), &mut state.class_list, None);
+    state.dummy = false;
+}
@@ -92,6 +92,7 @@ impl SpiderWindow {
                 .state
                 .borrow()
                 .selection
+                .as_ref()
                 .map(|s| s.address)
                 .unwrap_or_else(|| {
                     self.state
@@ -181,6 +182,28 @@ impl SpiderWindow {
                 let w = ui.available_width() / 2.;
                 show_edit(true, ui, &mut self.base_address, "Base address");
 
+                let mut instances = state
+                    .class_list
+                    .classes()
+                    .iter()
+                    .flat_map(|c| c.instances.iter().map(move |i| (&c.name, i)))
+                    .peekable();
+                if instances.peek().is_some() {
+                    ComboBox::new("_spider_select_instance", "Instance")
+                        .width(w + 8.)
+                        .selected_text("Use as base address")
+                        .show_ui(ui, |ui| {
+                            for (class, instance) in instances {
+                                let text = format!("{class}: {}", instance.label);
+                                if ui.selectable_label(false, text).clicked() {
+                                    if let Some(address) = instance.resolve() {
+                                        self.base_address.set(address, format!("{address:X}"));
+                                    }
+                                }
+                            }
+                        });
+                }
+
                 ui.horizontal(|ui| {
                     ui.add(TextEdit::singleline(&mut self.value_buf).desired_width(w));
                     if self.results.is_empty() {
@@ -1,11 +1,19 @@
-use super::{GeneratorWindow, MemflowAttachWindow, ProcessAttachWindow, ProcessInfoWindow, SpiderWindow};
+use super::{
+    FreezeListWindow, GeneratorWindow, GraphWindow, MemflowAttachWindow, ProcessAttachWindow,
+    ProcessInfoWindow, SpiderWindow, ValueHistoryWindow,
+};
 use crate::{
     class::ClassList,
     field::FieldKind,
+    project::Target,
     state::{GlobalState, StateRef},
+    tabs::InspectorState,
 };
 use eframe::{
-    egui::{Button, Context, CornerRadius, Frame, Margin, RichText, TopBottomPanel, Ui, WidgetText},
+    egui::{
+        Button, Context, CornerRadius, Event, Frame, Margin, RichText, TopBottomPanel, Ui,
+        ViewportCommand, WidgetText,
+    },
     epaint::{vec2, Color32},
 };
 use memflow::prelude::v1::*;
@@ -35,6 +43,13 @@ pub enum ToolBarResponse {
     Remove(usize),
     Insert(usize),
     ChangeKind(FieldKind),
+    Undo,
+    Redo,
+    CopyFields,
+    CutFields,
+    /// Pastes fields from the text, `None` pastes the fields that were copied last.
+    PasteFields(Option<String>),
+    ToggleWatchList,
 }
 
 pub struct ToolBarPanel {
@@ -42,7 +57,12 @@ pub struct ToolBarPanel {
     ps_attach_window: ProcessAttachWindow,
     ps_info_window: ProcessInfoWindow,
     generator_window: GeneratorWindow,
+    graph_window: GraphWindow,
     spider_window: SpiderWindow,
+    freeze_window: FreezeListWindow,
+    history_window: ValueHistoryWindow,
+    /// Paste of fields was requested, the text arrives as a paste event.
+    paste_requested: bool,
     state: StateRef,
 }
 
@@ -54,7 +74,11 @@ impl ToolBarPanel {
             ps_attach_window: ProcessAttachWindow::new(state),
             ps_info_window: ProcessInfoWindow::new(state),
             generator_window: GeneratorWindow::new(state),
+            graph_window: GraphWindow::new(state),
             spider_window: SpiderWindow::new(state),
+            freeze_window: FreezeListWindow::new(state),
+            history_window: ValueHistoryWindow::new(state),
+            paste_requested: false,
         }
     }
 
@@ -79,6 +103,9 @@ impl ToolBarPanel {
         self.ps_info_window.show(ctx);
 
         self.generator_window.show(ctx);
+        self.graph_window.show(ctx);
+        self.freeze_window.show(ctx);
+        self.history_window.show(ctx);
         if let Err(e) = self.spider_window.show(ctx) {
             self.state.borrow_mut().toasts.error(e.to_string());
         }
@@ -102,16 +129,33 @@ impl ToolBarPanel {
                     ui.visuals_mut().widgets.inactive.corner_radius = CornerRadius::ZERO;
 
                     ui.menu_button("Project", |ui| self.project_menu(ui));
+                    ui.menu_button("Edit", |ui| self.edit_menu(ui, &mut response));
                     ui.menu_button("Process", |ui| self.process_menu(ui, &mut response));
 
                     if ui.button("Generator").clicked() {
                         self.generator_window.toggle();
                     }
 
+                    if ui.button("Graph").clicked() {
+                        self.graph_window.toggle();
+                    }
+
                     if ui.button("Spider").clicked() {
                         self.spider_window.toggle();
                     }
 
+                    if ui.button("Freezes").clicked() {
+                        self.freeze_window.toggle();
+                    }
+
+                    if ui.button("History").clicked() {
+                        self.history_window.toggle();
+                    }
+
+                    if ui.button("Watches").clicked() {
+                        response = Some(ToolBarResponse::ToggleWatchList);
+                    }
+
                     ui.add_space(4.);
                     ui.separator();
                     ui.add_space(4.);
@@ -195,6 +239,31 @@ impl ToolBarPanel {
                 self.ps_attach_window.toggle();
             }
 
+            // Text edits have their own undo.
+            if !ctx.wants_keyboard_input() {
+                if hk_state.pressed("undo", input) {
+                    *response = Some(ToolBarResponse::Undo);
+                } else if hk_state.pressed("redo", input) {
+                    *response = Some(ToolBarResponse::Redo);
+                } else if hk_state.pressed("copy_fields", input) {
+                    *response = Some(ToolBarResponse::CopyFields);
+                } else if hk_state.pressed("cut_fields", input) {
+                    *response = Some(ToolBarResponse::CutFields);
+                } else if hk_state.pressed("paste_fields", input) {
+                    // Fields might have been copied in another instance.
+                    ctx.send_viewport_cmd(ViewportCommand::RequestPaste);
+                    self.paste_requested = true;
+                }
+
+                for event in input.events.iter() {
+                    if let Event::Paste(text) = event {
+                        if std::mem::take(&mut self.paste_requested) {
+                            *response = Some(ToolBarResponse::PasteFields(Some(text.clone())));
+                        }
+                    }
+                }
+            }
+
             if hk_state.pressed("attach_recent", input) {
                 true
             } else {
@@ -216,6 +285,11 @@ impl ToolBarPanel {
         if ui.button("New project").clicked() {
             state.save_project(None);
             state.class_list = ClassList::default();
+            state.watches.replace(vec![]);
+            state.history.clear();
+            state.selection = None;
+            state.inspector = InspectorState::default();
+            state.target = Target::default();
             ui.close_menu();
         }
 
@@ -297,10 +371,28 @@ impl ToolBarPanel {
 
         // Reattach to last process
         let state = &mut *self.state.borrow_mut();
-        if let Some(name) = state.config.last_attached_process_name.as_ref().cloned() {
+        let last = state.config.last_attached_process_name.clone();
+        if let Some(name) = last.as_ref() {
             if shortcut_button(ui, state, "attach_recent", format!("Attach to {name}")) {
-                attach_to_process(state, &name, response);
+                attach_to_process(state, name, response);
+
+                ui.close_menu();
+            }
+        }
 
+        // Process of the opened project
+        if let Some(name) = state
+            .target
+            .process
+            .clone()
+            .filter(|p| Some(p) != last.as_ref())
+        {
+            if ui
+                .button(format!("Attach to {name}"))
+                .on_hover_text("Process of the project")
+                .clicked()
+            {
+                attach_to_process(state, &name, response);
                 ui.close_menu();
             }
         }
@@ -311,6 +403,46 @@ impl ToolBarPanel {
         }
     }
 
+    fn edit_menu(&mut self, ui: &mut Ui, response: &mut Option<ToolBarResponse>) {
+        ui.set_width(160.);
+
+        let state = &*self.state.borrow();
+        ui.add_enabled_ui(state.history.can_undo(), |ui| {
+            if shortcut_button(ui, state, "undo", "Undo") {
+                *response = Some(ToolBarResponse::Undo);
+                ui.close_menu();
+            }
+        });
+
+        ui.add_enabled_ui(state.history.can_redo(), |ui| {
+            if shortcut_button(ui, state, "redo", "Redo") {
+                *response = Some(ToolBarResponse::Redo);
+                ui.close_menu();
+            }
+        });
+
+        ui.separator();
+
+        ui.add_enabled_ui(state.selection.is_some(), |ui| {
+            if shortcut_button(ui, state, "copy_fields", "Copy fields") {
+                *response = Some(ToolBarResponse::CopyFields);
+                ui.close_menu();
+            }
+
+            if shortcut_button(ui, state, "cut_fields", "Cut fields") {
+                *response = Some(ToolBarResponse::CutFields);
+                ui.close_menu();
+            }
+        });
+
+        ui.add_enabled_ui(state.copied_fields.is_some(), |ui| {
+            if shortcut_button(ui, state, "paste_fields", "Paste fields") {
+                *response = Some(ToolBarResponse::PasteFields(None));
+                ui.close_menu();
+            }
+        });
+    }
+
     fn status_ui(&mut self, ui: &mut Ui, response: &mut Option<ToolBarResponse>) {
         if let Some((proc_name, proc_id)) = self
             .state
@@ -334,6 +466,24 @@ impl ToolBarPanel {
         } else {
             ui.label("Status: Detached");
         }
+
+        ui.add_space(8.);
+
+        let state = &mut *self.state.borrow_mut();
+        let mut read_only = state.is_read_only();
+        let forced = state.is_read_only_forced();
+        let r = ui
+            .add_enabled_ui(!forced, |ui| ui.toggle_value(&mut read_only, "Read-only"))
+            .inner;
+        if r.changed() {
+            state.set_read_only(read_only);
+        }
+
+        if forced {
+            r.on_disabled_hover_text("Offline dump sources are always read-only");
+        } else {
+            r.on_hover_text("Blocks all writes to the process memory");
+        }
     }
 
     fn field_change_ui(&mut self, ui: &mut Ui, response: &mut Option<ToolBarResponse>) {
@@ -362,7 +512,14 @@ impl ToolBarPanel {
         ui.separator();
         ui.add_space(2.);
 
-        create_change_field_type_group!(ui, response, BLACK, BROWN, Ptr, StrPtr);
+        create_change_field_type_group!(
+            ui, response, BLACK, BROWN, Ptr, RelPtr32, RelPtr64, StrPtr, FnPtr
+        );
+
+        ui.separator();
+        ui.add_space(2.);
+
+        create_change_field_type_group!(ui, response, BLACK, KHAKI, Union);
     }
 }
 
@@ -0,0 +1,116 @@
+use crate::{field::plot_values, state::StateRef, FID_M};
+use eframe::{
+    egui::{Context, DragValue, RichText, ScrollArea, Window},
+    epaint::{vec2, Color32},
+};
+
+/// Window that plots recent values of the fields picked from their context menu.
+pub struct ValueHistoryWindow {
+    shown: bool,
+    /// Amount of plots during the last frame, used to open the window for a new one.
+    plot_count: usize,
+    state: StateRef,
+}
+
+impl ValueHistoryWindow {
+    pub fn new(state: StateRef) -> Self {
+        Self {
+            shown: false,
+            plot_count: 0,
+            state,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.shown = !self.shown;
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        let state = &mut *self.state.borrow_mut();
+        let plots = state.changes.plots();
+
+        // Plotted fields are read even when they aren't drawn in the inspector.
+        if let Some(process) = state.process.read().as_ref() {
+            for plot in plots.iter() {
+                let mut buf = vec![0; plot.kind.size()];
+                process.read(plot.address, &mut buf);
+                state.changes.observe(plot.address, &buf);
+            }
+        }
+
+        if plots.len() > self.plot_count {
+            self.shown = true;
+        }
+        self.plot_count = plots.len();
+
+        if !self.shown {
+            return;
+        }
+
+        Window::new("Value history")
+            .open(&mut self.shown)
+            .default_size(vec2(420., 320.))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Highlight changes for");
+
+                    let mut fade = state.changes.fade_ms();
+                    let r = ui.add(DragValue::new(&mut fade).range(0..=60_000).suffix(" ms"));
+                    if r.changed() {
+                        state.changes.set_fade_ms(fade);
+                        state.config.highlight_fade_ms = Some(fade);
+                    }
+                    if r.drag_stopped() || r.lost_focus() {
+                        state.config.save();
+                    }
+                });
+
+                ui.separator();
+
+                if plots.is_empty() {
+                    ui.label("Right click a named field to plot its values.");
+                    return;
+                }
+
+                let mut remove = None;
+                ScrollArea::vertical().show(ui, |ui| {
+                    for (i, plot) in plots.iter().enumerate() {
+                        let history = state.changes.history(plot.address, plot.kind);
+
+                        ui.horizontal(|ui| {
+                            ui.label(&plot.label);
+                            ui.label(
+                                RichText::new(format!("{:X}", plot.address))
+                                    .font(FID_M)
+                                    .color(Color32::LIGHT_GREEN),
+                            );
+                            if ui.small_button("x").clicked() {
+                                remove = Some(i);
+                            }
+                        });
+
+                        let width = ui.available_width();
+                        plot_values(ui, &history, vec2(width, 80.));
+
+                        let (min, max) = history
+                            .iter()
+                            .fold((f64::MAX, f64::MIN), |(min, max), (_, v)| {
+                                (min.min(*v), max.max(*v))
+                            });
+                        if let Some((_, current)) = history.last() {
+                            ui.label(
+                                RichText::new(format!("min {min}  max {max}  current {current}"))
+                                    .font(FID_M)
+                                    .color(Color32::GRAY),
+                            );
+                        }
+                        ui.separator();
+                    }
+                });
+
+                if let Some(i) = remove {
+                    state.changes.unplot(i);
+                }
+            });
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{
+    history::Command,
+    state::{GlobalState, StateRef},
+    watch::{format_value, parse_value, Watch},
+    FID_M,
+};
+use eframe::{
+    egui::{Context, Grid, Key, Label, RichText, ScrollArea, Sense, SidePanel, TextEdit, Ui},
+    epaint::Color32,
+};
+
+/// Panel docked to the right side, shows watched fields whichever class is inspected.
+pub struct WatchListPanel {
+    shown: bool,
+    /// Amount of watches during the last frame, used to show the panel for a new one.
+    watch_count: usize,
+    /// Index of the watch whose value is edited and the entered text.
+    editing: Option<(usize, String)>,
+    should_focus: bool,
+    state: StateRef,
+}
+
+impl WatchListPanel {
+    pub fn new(state: StateRef) -> Self {
+        Self {
+            shown: false,
+            watch_count: 0,
+            editing: None,
+            should_focus: false,
+            state,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.shown = !self.shown;
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        let count = self.state.borrow().watches.list().len();
+        if count > self.watch_count {
+            self.shown = true;
+        }
+        self.watch_count = count;
+
+        if !self.shown {
+            return;
+        }
+
+        SidePanel::right("_watch_list")
+            .resizable(true)
+            .default_width(360.)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading("Watch list");
+                    if ui.small_button("x").on_hover_text("Hide").clicked() {
+                        self.shown = false;
+                    }
+                });
+                ui.separator();
+
+                self.ui(ui);
+            });
+    }
+
+    fn ui(&mut self, ui: &mut Ui) {
+        let state = &mut *self.state.borrow_mut();
+        let watches = state.watches.list();
+        if watches.is_empty() {
+            ui.label("Right click a named field to add it to the watch list.");
+            return;
+        }
+
+        let mut remove = None;
+        let mut write = None;
+        ScrollArea::both().show(ui, |ui| {
+            Grid::new("_watch_list_grid").striped(true).show(ui, |ui| {
+                let process = state.process.read();
+                for (i, watch) in watches.iter().enumerate() {
+                    ui.label(&watch.label)
+                        .on_hover_text(watch.describe(&state.class_list));
+
+                    let address = process
+                        .as_ref()
+                        .and_then(|p| Some((p, watch.resolve(&state.class_list, p)?)));
+                    let Some((process, address)) = address else {
+                        ui.label(RichText::new("?").color(Color32::RED))
+                            .on_hover_text("Path can't be resolved");
+                        ui.label("");
+                        ui.label("");
+                        if ui.small_button("x").clicked() {
+                            remove = Some(i);
+                        }
+                        ui.end_row();
+                        continue;
+                    };
+
+                    ui.label(
+                        RichText::new(format!("{address:X}"))
+                            .font(FID_M)
+                            .color(Color32::LIGHT_GREEN),
+                    );
+                    ui.label(
+                        RichText::new(
+                            process
+                                .locate(address)
+                                .map(|l| l.describe())
+                                .unwrap_or_default(),
+                        )
+                        .color(Color32::GRAY),
+                    );
+
+                    let mut buf = vec![0; watch.kind.size()];
+                    process.read(address, &mut buf);
+                    if let Some(text) = self.value_ui(ui, i, watch, &buf) {
+                        if process.is_read_only() {
+                            state
+                                .toasts
+                                .warning("Read-only mode is enabled, values can't be edited");
+                            self.editing = None;
+                        } else {
+                            write = Some((address, parse_value(watch.kind, &text)));
+                        }
+                    }
+
+                    if ui.small_button("x").clicked() {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+
+        match write {
+            Some((address, Some(bytes))) => {
+                Self::write(state, address, bytes);
+                self.editing = None;
+            }
+            Some((_, None)) => {
+                state.toasts.error("Invalid value");
+                self.should_focus = true;
+            }
+            None => {}
+        }
+
+        if let Some(i) = remove {
+            state.watches.remove(i);
+            self.editing = None;
+        }
+    }
+
+    /// Shows the value, clicking it starts editing. Returns the entered text once editing is done.
+    fn value_ui(&mut self, ui: &mut Ui, index: usize, watch: &Watch, buf: &[u8]) -> Option<String> {
+        let value = format_value(watch.kind, buf);
+
+        match self.editing.as_mut() {
+            Some((i, text)) if *i == index => {
+                let r = ui.add(TextEdit::singleline(text).desired_width(120.).font(FID_M));
+                if self.should_focus {
+                    r.request_focus();
+                    self.should_focus = false;
+                }
+
+                if r.clicked_elsewhere() || ui.input(|i| i.key_pressed(Key::Escape)) {
+                    self.editing = None;
+                } else if r.lost_focus() {
+                    return Some(text.clone());
+                }
+            }
+            _ => {
+                let r = ui
+                    .add(
+                        Label::new(RichText::new(&value).font(FID_M).color(Color32::WHITE))
+                            .sense(Sense::click()),
+                    )
+                    .on_hover_text("Click to edit");
+                if r.clicked() {
+                    self.editing = Some((index, value));
+                    self.should_focus = true;
+                }
+            }
+        }
+
+        None
+    }
+
+    fn write(state: &mut GlobalState, address: usize, bytes: Vec<u8>) {
+        let process = state.process.read();
+        let Some(process) = process.as_ref() else {
+            return;
+        };
+
+        let mut old = vec![0; bytes.len()];
+        process.read(address, &mut old);
+        if process.write(address, &bytes) {
+            state.history.push(Command::Write {
+                pid: process.id(),
+                address,
+                bytes: old,
+            });
+        } else {
+            state.toasts.error(process.write_error());
+        }
+    }
+}
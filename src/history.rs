@@ -0,0 +1,458 @@
+//! This module contains the undo/redo history of edits.
+//! Every command is stored already applied, applying it again returns its inverse.
+use crate::{
+    class::{Class, ClassId, ClassList},
+    field::{Field, FieldId},
+    process::YProcess,
+};
+
+/// Maximum amount of commands that can be undone.
+const MAX_LEN: usize = 512;
+
+pub enum Command {
+    /// Replaces `len` fields of the class starting at `at` with `fields`.
+    Splice {
+        class_id: ClassId,
+        at: usize,
+        len: usize,
+        fields: Vec<Box<dyn Field>>,
+    },
+    InsertClass {
+        index: usize,
+        class: Box<Class>,
+    },
+    RemoveClass(ClassId),
+    RenameClass {
+        class_id: ClassId,
+        name: String,
+    },
+    SetBase {
+        class_id: ClassId,
+        base: Option<ClassId>,
+    },
+    /// Moves the class to the namespace, empty for the global one.
+    SetNamespace {
+        class_id: ClassId,
+        namespace: String,
+    },
+    /// Declares the size and the alignment of the class.
+    SetLayout {
+        class_id: ClassId,
+        size: Option<usize>,
+        alignment: Option<usize>,
+    },
+    /// Makes pointers of the field that point to the class `from` point to `to`.
+    Retarget {
+        class_id: ClassId,
+        field_id: FieldId,
+        from: ClassId,
+        to: ClassId,
+    },
+    /// Renames the named field, which may be a member of a union.
+    RenameField {
+        class_id: ClassId,
+        field_id: FieldId,
+        name: String,
+    },
+    /// Writes `bytes` at `address` of the process with `pid`.
+    Write {
+        pid: u32,
+        address: usize,
+        bytes: Vec<u8>,
+    },
+    /// Commands applied in order, e.g. removal of multiple classes.
+    Batch(Vec<Command>),
+}
+
+impl Command {
+    pub fn writes_memory(&self) -> bool {
+        match self {
+            Self::Write { .. } => true,
+            Self::Batch(commands) => commands.iter().any(Self::writes_memory),
+            _ => false,
+        }
+    }
+
+    /// Applies the command and returns its inverse.
+    /// `None` is returned if the command couldn't be applied.
+    pub fn apply(self, list: &mut ClassList, process: Option<&YProcess>) -> Option<Command> {
+        Some(match self {
+            Self::Splice {
+                class_id,
+                at,
+                len,
+                fields,
+            } => {
+                let class = list.by_id_mut(class_id)?;
+                if at + len > class.fields.len() {
+                    return None;
+                }
+
+                let inserted = fields.len();
+                let removed = class.fields.splice(at..at + len, fields).collect();
+
+                Self::Splice {
+                    class_id,
+                    at,
+                    len: inserted,
+                    fields: removed,
+                }
+            }
+            Self::InsertClass { index, class } => {
+                let id = class.id();
+                list.insert(index, *class);
+                Self::RemoveClass(id)
+            }
+            Self::RemoveClass(id) => {
+                let (index, class) = list.remove(id)?;
+                Self::InsertClass {
+                    index,
+                    class: Box::new(class),
+                }
+            }
+            Self::RenameClass { class_id, name } => {
+                let class = list.by_id_mut(class_id)?;
+                Self::RenameClass {
+                    class_id,
+                    name: std::mem::replace(&mut class.name, name),
+                }
+            }
+            Self::SetBase { class_id, base } => {
+                let class = list.by_id_mut(class_id)?;
+                Self::SetBase {
+                    class_id,
+                    base: std::mem::replace(&mut class.base, base),
+                }
+            }
+            Self::SetNamespace {
+                class_id,
+                namespace,
+            } => {
+                let class = list.by_id_mut(class_id)?;
+                Self::SetNamespace {
+                    class_id,
+                    namespace: std::mem::replace(&mut class.namespace, namespace),
+                }
+            }
+            Self::SetLayout {
+                class_id,
+                size,
+                alignment,
+            } => {
+                let class = list.by_id_mut(class_id)?;
+                Self::SetLayout {
+                    class_id,
+                    size: std::mem::replace(&mut class.size, size),
+                    alignment: std::mem::replace(&mut class.alignment, alignment),
+                }
+            }
+            Self::Retarget {
+                class_id,
+                field_id,
+                from,
+                to,
+            } => {
+                let class = list.by_id(class_id)?;
+                class
+                    .fields
+                    .iter()
+                    .find(|f| f.id() == field_id)?
+                    .retarget(from, to);
+
+                Self::Retarget {
+                    class_id,
+                    field_id,
+                    from: to,
+                    to: from,
+                }
+            }
+            Self::RenameField {
+                class_id,
+                field_id,
+                name,
+            } => {
+                let class = list.by_id(class_id)?;
+                let name = class
+                    .fields
+                    .iter()
+                    .find_map(|f| f.rename(field_id, &name))?;
+
+                Self::RenameField {
+                    class_id,
+                    field_id,
+                    name,
+                }
+            }
+            Self::Write {
+                pid,
+                address,
+                bytes,
+            } => {
+                // Bytes of another process must not be written after reattaching.
+                let process = process.filter(|p| p.id() == pid)?;
+
+                let mut old = vec![0; bytes.len()];
+                process.read(address, &mut old);
+                if !process.write(address, &bytes) {
+                    return None;
+                }
+
+                Self::Write {
+                    pid,
+                    address,
+                    bytes: old,
+                }
+            }
+            Self::Batch(commands) => {
+                let mut inverse = Vec::with_capacity(commands.len());
+                for command in commands {
+                    match command.apply(list, process) {
+                        Some(i) => inverse.push(i),
+                        None => {
+                            // Already applied commands are reverted, so the batch is all or none.
+                            for i in inverse.into_iter().rev() {
+                                i.apply(list, process);
+                            }
+                            return None;
+                        }
+                    }
+                }
+                inverse.reverse();
+
+                Self::Batch(inverse)
+            }
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Command>,
+    redo: Vec<Command>,
+}
+
+impl History {
+    /// Applies the command and records it.
+    pub fn apply(&mut self, command: Command, list: &mut ClassList, process: Option<&YProcess>) {
+        if let Some(inverse) = command.apply(list, process) {
+            self.push(inverse);
+        }
+    }
+
+    /// Records the inverse of the command that was already applied.
+    pub fn push(&mut self, inverse: Command) {
+        if self.undo.len() == MAX_LEN {
+            self.undo.remove(0);
+        }
+
+        self.undo.push(inverse);
+        self.redo.clear();
+    }
+
+    /// Returns `false` if there is nothing to undo or the last command couldn't be undone,
+    /// in which case it is discarded. Memory writes are kept until they can be undone.
+    pub fn undo(&mut self, list: &mut ClassList, process: Option<&YProcess>) -> bool {
+        if !self.undo.last().is_some_and(|c| can_apply(c, process)) {
+            return false;
+        }
+        let command = self.undo.pop().unwrap();
+
+        match command.apply(list, process) {
+            Some(inverse) => {
+                self.redo.push(inverse);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `false` if there is nothing to redo or the last command couldn't be redone,
+    /// in which case it is discarded. Memory writes are kept until they can be redone.
+    pub fn redo(&mut self, list: &mut ClassList, process: Option<&YProcess>) -> bool {
+        if !self.redo.last().is_some_and(|c| can_apply(c, process)) {
+            return false;
+        }
+        let command = self.redo.pop().unwrap();
+
+        match command.apply(list, process) {
+            Some(inverse) => {
+                self.undo.push(inverse);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+/// Memory writes can only be applied to an attached process outside of the read-only mode.
+fn can_apply(command: &Command, process: Option<&YProcess>) -> bool {
+    !command.writes_memory() || process.is_some_and(|p| !p.is_read_only())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::FieldKind;
+
+    fn list() -> (ClassList, ClassId) {
+        let mut list = ClassList::EMPTY;
+        let id = list.add_empty_class("A".into());
+        list.by_id_mut(id).unwrap().fields = vec![
+            FieldKind::U32.into_field(Some("a".into())),
+            FieldKind::F32.into_field(Some("b".into())),
+        ];
+        (list, id)
+    }
+
+    fn names(list: &ClassList, id: ClassId) -> Vec<String> {
+        let class = list.by_id(id).unwrap();
+        class.fields.iter().filter_map(|f| f.name()).collect()
+    }
+
+    /// Applies the command and its inverse, the list must be the same as before.
+    fn check_inverse(command: impl Fn(ClassId, &ClassList) -> Command) {
+        let (mut list, id) = list();
+        let before = snapshot(&list);
+
+        let inverse = command(id, &list).apply(&mut list, None).unwrap();
+        assert_ne!(snapshot(&list), before);
+        inverse.apply(&mut list, None).unwrap();
+        assert_eq!(snapshot(&list), before);
+    }
+
+    /// State of every class the commands edit.
+    fn snapshot(list: &ClassList) -> Vec<String> {
+        list.classes()
+            .iter()
+            .map(|c| {
+                let fields = names(list, c.id());
+                let layout = (c.size, c.alignment);
+                format!(
+                    "{} {fields:?} {:?} {} {layout:?}",
+                    c.name, c.base, c.namespace
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn inverses_undo_commands() {
+        check_inverse(|class_id, _| Command::Splice {
+            class_id,
+            at: 1,
+            len: 1,
+            fields: vec![FieldKind::U8.into_field(Some("c".into()))],
+        });
+        check_inverse(|class_id, _| Command::RenameClass {
+            class_id,
+            name: "B".into(),
+        });
+        check_inverse(|class_id, _| Command::SetBase {
+            class_id,
+            base: Some(class_id),
+        });
+        check_inverse(|class_id, _| Command::SetNamespace {
+            class_id,
+            namespace: "ns".into(),
+        });
+        check_inverse(|class_id, _| Command::SetLayout {
+            class_id,
+            size: Some(16),
+            alignment: None,
+        });
+        check_inverse(|class_id, list| Command::RenameField {
+            class_id,
+            field_id: list.by_id(class_id).unwrap().fields[0].id(),
+            name: "renamed".into(),
+        });
+        check_inverse(|class_id, _| Command::RemoveClass(class_id));
+        check_inverse(|class_id, _| {
+            Command::Batch(vec![
+                Command::RenameClass {
+                    class_id,
+                    name: "B".into(),
+                },
+                Command::SetNamespace {
+                    class_id,
+                    namespace: "ns".into(),
+                },
+            ])
+        });
+    }
+
+    #[test]
+    fn invalid_commands_are_not_applied() {
+        let (mut list, id) = list();
+        let splice = Command::Splice {
+            class_id: id,
+            at: 2,
+            len: 1,
+            fields: vec![],
+        };
+        assert!(splice.apply(&mut list, None).is_none());
+        assert!(Command::RemoveClass(id + 1)
+            .apply(&mut list, None)
+            .is_none());
+
+        let write = Command::Write {
+            pid: 0,
+            address: 0,
+            bytes: vec![0],
+        };
+        assert!(write.apply(&mut list, None).is_none());
+    }
+
+    #[test]
+    fn failed_batches_are_reverted() {
+        let (mut list, id) = list();
+        let batch = Command::Batch(vec![
+            Command::RenameClass {
+                class_id: id,
+                name: "B".into(),
+            },
+            Command::RemoveClass(id + 1),
+        ]);
+
+        assert!(batch.apply(&mut list, None).is_none());
+        assert_eq!(list.by_id(id).unwrap().name, "A");
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let (mut list, id) = list();
+        let mut history = History::default();
+        let rename = |name: &str| Command::RenameClass {
+            class_id: id,
+            name: name.into(),
+        };
+
+        history.apply(rename("B"), &mut list, None);
+        history.apply(rename("C"), &mut list, None);
+        assert!(history.undo(&mut list, None));
+        assert_eq!(list.by_id(id).unwrap().name, "B");
+        assert!(history.redo(&mut list, None));
+        assert_eq!(list.by_id(id).unwrap().name, "C");
+        assert!(!history.redo(&mut list, None));
+
+        assert!(history.undo(&mut list, None));
+        history.apply(rename("D"), &mut list, None);
+        assert!(!history.can_redo());
+        assert!(history.undo(&mut list, None));
+        assert!(history.undo(&mut list, None));
+        assert_eq!(list.by_id(id).unwrap().name, "A");
+        assert!(!history.undo(&mut list, None));
+    }
+}
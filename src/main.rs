@@ -8,17 +8,25 @@ compile_error!("Only X64 targets are supported.");
 
 mod address;
 mod app;
+mod changes;
 mod class;
 mod config;
 mod context;
 mod field;
+mod freeze;
 mod generator;
+mod graph;
+mod guess;
 mod gui;
+mod history;
 mod hotkeys;
 mod process;
 mod project;
+mod rtti;
 mod state;
+mod tabs;
 mod value;
+mod watch;
 
 use config::YClassConfig;
 use eframe::{
@@ -67,6 +75,12 @@ fn main() {
             hotkeys.register("attach_process", Key::A, Modifiers::ALT);
             hotkeys.register("attach_recent", Key::A, Modifiers::ALT | Modifiers::CTRL);
             hotkeys.register("detach_process", Key::D, Modifiers::ALT);
+            hotkeys.register("undo", Key::Z, Modifiers::CTRL);
+            hotkeys.register("redo", Key::Y, Modifiers::CTRL);
+            // `Ctrl-C` copies the address of the selected field.
+            hotkeys.register("copy_fields", Key::C, Modifiers::ALT);
+            hotkeys.register("cut_fields", Key::X, Modifiers::ALT);
+            hotkeys.register("paste_fields", Key::V, Modifiers::ALT);
 
             Ok(Box::new(app::YClassApp::new(Box::leak(Box::new(RefCell::new(
                 GlobalState {
@@ -1,16 +1,65 @@
 use memflow::{os::Process, os::Os, prelude::*};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// How often the list of modules is refreshed.
+const MODULES_TTL: Duration = Duration::from_secs(5);
+/// How often the memory map is refreshed, it changes more often than modules do.
+const MAPS_TTL: Duration = Duration::from_secs(1);
 
 pub struct YProcess {
     inner: RwLock<IntoProcessInstanceArcBox<'static>>,
+    modules: Mutex<Option<(Instant, Vec<ModuleInfo>)>>,
+    /// Mapped ranges sorted by address, queried for every pointer each frame.
+    maps: Mutex<Option<(Instant, Vec<MemoryRange>)>>,
+    /// Exports sorted by offset, keyed by the base of the module.
+    exports: Mutex<HashMap<usize, Vec<(usize, String)>>>,
+    /// Shared with [`crate::state::GlobalState`], blocks all writes when set.
+    read_only: Arc<AtomicBool>,
+}
+
+/// Position of an address inside of a module.
+pub struct Location {
+    pub module: String,
+    pub offset: usize,
+    /// Closest preceding export and the offset from it.
+    pub symbol: Option<(String, usize)>,
+}
+
+impl Location {
+    /// Formats location as `module+0x10 (symbol+0x4)`.
+    pub fn describe(&self) -> String {
+        let mut text = format!("{}+{:#X}", self.module, self.offset);
+        match &self.symbol {
+            Some((name, 0)) => text += &format!(" ({name})"),
+            Some((name, off)) => text += &format!(" ({name}+{off:#X})"),
+            None => {}
+        }
+        text
+    }
 }
 
 impl YProcess {
-    pub fn attach(os: OsInstanceArcBox<'static>, pid: u32) -> Result<Self> {
+    pub fn attach(
+        os: OsInstanceArcBox<'static>,
+        pid: u32,
+        read_only: Arc<AtomicBool>,
+    ) -> Result<Self> {
         // TODO: maps
         let inner = os.into_process_by_pid(pid)?;
         Ok(Self {
             inner: RwLock::new(inner),
+            modules: Mutex::default(),
+            maps: Mutex::default(),
+            exports: Mutex::default(),
+            read_only,
         })
     }
 
@@ -18,8 +67,26 @@ impl YProcess {
         self.inner.write().read_into(address.into(), buf).ok();
     }
 
-    pub fn write(&self, address: usize, buf: &[u8]) {
-        self.inner.write().write(address.into(), buf).ok();
+    /// Returns `false` if the write was blocked by the read-only mode or failed.
+    pub fn write(&self, address: usize, buf: &[u8]) -> bool {
+        if self.is_read_only() {
+            return false;
+        }
+
+        self.inner.write().write(address.into(), buf).is_ok()
+    }
+
+    /// Explains why [`YProcess::write`] returned `false`.
+    pub fn write_error(&self) -> &'static str {
+        if self.is_read_only() {
+            "Read-only mode is enabled, memory wasn't written"
+        } else {
+            "Failed to write the memory"
+        }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::Relaxed)
     }
 
     pub fn id(&self) -> u32 {
@@ -41,4 +108,78 @@ impl YProcess {
         let p = self.inner.read();
         p.info().clone()
     }
+
+    /// Finds the module containing `address` and the closest export before it.
+    pub fn locate(&self, address: usize) -> Option<Location> {
+        let mut modules = self.modules.lock();
+        if modules
+            .as_ref()
+            .is_none_or(|(t, _)| t.elapsed() > MODULES_TTL)
+        {
+            let list = self.inner.write().module_list().unwrap_or_default();
+            *modules = Some((Instant::now(), list));
+        }
+
+        let module = modules.as_ref()?.1.iter().find(|m| {
+            let base = m.base.to_umem() as usize;
+            (base..base + m.size as usize).contains(&address)
+        })?;
+
+        let base = module.base.to_umem() as usize;
+        let offset = address - base;
+
+        let mut exports = self.exports.lock();
+        let exports = exports.entry(base).or_insert_with(|| {
+            let mut list = self
+                .inner
+                .write()
+                .module_export_list(module)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|e| (e.offset as usize, e.name.to_string()))
+                .collect::<Vec<_>>();
+            list.sort_unstable_by_key(|(off, _)| *off);
+            list
+        });
+
+        let symbol = match exports.partition_point(|(off, _)| *off <= offset) {
+            0 => None,
+            i => Some((exports[i - 1].1.clone(), offset - exports[i - 1].0)),
+        };
+
+        Some(Location {
+            module: module.name.to_string(),
+            offset,
+            symbol,
+        })
+    }
+
+    /// Page type of the mapped range containing `address`.
+    fn page_type(&self, address: usize) -> Option<PageType> {
+        let mut maps = self.maps.lock();
+        if maps.as_ref().is_none_or(|(t, _)| t.elapsed() > MAPS_TTL) {
+            let mut list = self.inner.write().mapped_mem_vec(0);
+            list.sort_unstable_by_key(|r| r.0);
+            *maps = Some((Instant::now(), list));
+        }
+
+        let ranges = &maps.as_ref()?.1;
+        let range = match ranges.partition_point(|r| r.0.to_umem() as usize <= address) {
+            0 => return None,
+            i => &ranges[i - 1],
+        };
+        let start = range.0.to_umem() as usize;
+        (address - start < range.1 as usize).then_some(range.2)
+    }
+
+    /// Checks if `address` is mapped at all.
+    pub fn is_mapped(&self, address: usize) -> bool {
+        self.page_type(address).is_some()
+    }
+
+    /// Checks if `address` is mapped as executable memory.
+    pub fn is_executable(&self, address: usize) -> bool {
+        self.page_type(address)
+            .is_some_and(|t| !t.contains(PageType::NOEXEC))
+    }
 }
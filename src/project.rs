@@ -1,64 +1,282 @@
 /// This module contains structures that serialize/deserialize project data(i.e. classes).
 use crate::{
-    class::{Class, ClassList},
-    field::{allocate_padding, CodegenData, Field, FieldKind, PointerField},
+    address::parse_address,
+    class::{is_valid_namespace, Class, ClassId, ClassList, Instance},
+    field::{
+        allocate_padding, split_pointer_metadata, CodegenData, DisplayFormat, Field, FieldKind,
+        PointerField, UnionField,
+    },
     generator::Generator,
+    tabs::{InspectorState, InspectorTab},
+    watch::{Link, Watch, WatchPath},
 };
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Version of the project format written by this build.
+const VERSION: u32 = 1;
+
+/// Upgrades of older projects, `MIGRATIONS[i]` upgrades the project of version `i` to `i + 1`.
+const MIGRATIONS: &[fn(&mut ProjectData)] = &[migrate_v0];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct DataField {
     name: String,
     offset: usize,
-    kind: FieldKind,
+    /// `None` if the kind is unknown to this version, such fields are loaded as padding.
+    #[serde(with = "known_kind")]
+    kind: Option<FieldKind>,
     metadata: Option<String>,
+    /// Size of the field, so fields of unknown kinds can be replaced with padding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<usize>,
+    /// Members of the union, offsets are relative to the union.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    members: Option<Vec<DataField>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tag_offset: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    format: Option<DisplayFormat>,
+    /// Class the pointer points to, only stored in the clipboard.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    class_id: Option<ClassId>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    note: String,
+    /// Pointer is expanded in the inspector.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    expanded: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct DataClass {
     name: String,
     fields: Vec<DataField>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    instances: Vec<Instance>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    namespace: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    alignment: Option<usize>,
+    /// Address expression the class is inspected at, overrides the first instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    note: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DataWatch {
+    /// Name of the class the path starts from.
+    class: String,
+    /// Address the path starts at instead of the address of the class.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    links: Vec<Link>,
+    offset: usize,
+    #[serde(with = "known_kind")]
+    kind: Option<FieldKind>,
+    label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DataTab {
+    class: String,
+    address: String,
+}
+
+/// Layout of the inspector, classes are referenced by names.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DataInspector {
+    /// Class selected in the class list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    selected: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tabs: Vec<DataTab>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    active: Option<usize>,
+}
+
+/// Process the project inspects and the connector used to reach it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Target {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connector: Option<String>,
+}
+
+/// Everything restored from the project.
+pub struct LoadedProject {
+    pub class_list: ClassList,
+    pub watches: Vec<Watch>,
+    pub inspector: InspectorState,
+    pub target: Target,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct ProjectData {
+    /// Projects saved before versioning are version 0.
+    #[serde(default)]
+    version: u32,
     classes: Vec<DataClass>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    watches: Vec<DataWatch>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target: Option<Target>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inspector: Option<DataInspector>,
+}
+
+/// Reads only the version, so newer projects are reported even if they can't be parsed.
+#[derive(Deserialize)]
+struct ProjectHeader {
+    #[serde(default)]
+    version: u32,
+}
+
+/// Reason the project couldn't be opened.
+#[derive(Debug)]
+pub enum ProjectError {
+    /// Text isn't valid RON or doesn't match the project format.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Project was saved by a newer version of YClass.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "Line {line}, column {column}: {message}"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "Project version {version} is newer than the supported version {VERSION}"
+            ),
+        }
+    }
+}
+
+impl From<ron::error::SpannedError> for ProjectError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        Self::Parse {
+            line: e.position.line,
+            column: e.position.col,
+            message: e.code.to_string(),
+        }
+    }
+}
+
+/// Largest amount of bytes that can be pasted at once.
+const MAX_CLIPBOARD_SIZE: usize = 0x10_0000;
+
+/// Fields copied to the clipboard. Stored as text, so they can be pasted in other instances too.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldClipboard {
+    /// Instance the fields were copied from, class ids are only valid in it.
+    instance: u64,
+    size: usize,
+    fields: Vec<DataField>,
 }
 
 #[derive(Default, Clone)]
 struct ProjectDataGenerator {
     classes: Vec<DataClass>,
+    with_class_ids: bool,
     offset: usize,
     last_offset: usize,
+    in_union: bool,
 }
 
 impl Generator for &mut ProjectDataGenerator {
-    fn begin_class(&mut self, name: &str) {
+    fn begin_class(&mut self, name: &str, base: Option<&str>) {
         self.classes.push(DataClass {
             name: name.into(),
             fields: vec![],
+            base: base.map(|b| b.to_owned()),
+            instances: vec![],
+            namespace: String::new(),
+            size: None,
+            alignment: None,
+            address: None,
+            note: String::new(),
         });
     }
 
     fn add_field(&mut self, name: &str, kind: FieldKind, metadata: Option<&str>) {
         let size = kind.size();
 
-        self.classes.last_mut().unwrap().fields.push(DataField {
+        let fields = &mut self.classes.last_mut().unwrap().fields;
+        let field = DataField {
             metadata: metadata.map(|s| s.to_owned()),
             name: name.to_owned(),
             offset: self.offset,
-            kind,
-        });
+            kind: Some(kind),
+            size: Some(size),
+            members: None,
+            tag_offset: None,
+            format: None,
+            class_id: None,
+            note: String::new(),
+            expanded: false,
+        };
 
-        self.offset += size;
-        self.last_offset = self.offset;
+        if self.in_union {
+            let union = fields.last_mut().unwrap();
+            union
+                .members
+                .get_or_insert_with(Vec::new)
+                .push(DataField { offset: 0, ..field });
+        } else {
+            fields.push(field);
+
+            self.offset += size;
+            self.last_offset = self.offset;
+        }
     }
 
     fn add_offset(&mut self, offset: usize) {
         self.offset += offset;
     }
 
+    fn begin_union(&mut self, name: &str, tag_offset: Option<usize>) {
+        self.classes.last_mut().unwrap().fields.push(DataField {
+            name: name.to_owned(),
+            offset: self.offset,
+            kind: Some(FieldKind::Union),
+            metadata: None,
+            size: None,
+            members: Some(vec![]),
+            tag_offset,
+            format: None,
+            class_id: None,
+            note: String::new(),
+            expanded: false,
+        });
+        self.in_union = true;
+    }
+
+    fn end_union(&mut self, size: usize) {
+        if let Some(union) = self.classes.last_mut().and_then(|c| c.fields.last_mut()) {
+            union.size = Some(size);
+        }
+        self.in_union = false;
+        self.offset += size;
+        self.last_offset = self.offset;
+    }
+
     fn end_class(&mut self) {
         self.offset = 0;
         self.last_offset = 0;
@@ -69,94 +287,491 @@ impl Generator for &mut ProjectDataGenerator {
     }
 }
 
+impl ProjectDataGenerator {
+    fn add(&mut self, field: &dyn Field, data: &CodegenData) {
+        let offset = self.offset;
+        field.codegen(&mut &mut *self, data);
+
+        if field.name().is_some() {
+            self.annotate_last(field);
+        } else if let Some(semantic) = field.semantic() {
+            // Hex fields are only stored to keep their semantic.
+            self.classes.last_mut().unwrap().fields.push(DataField {
+                offset,
+                kind: Some(field.kind()),
+                metadata: Some(semantic.to_metadata()),
+                size: Some(field.size()),
+                ..Default::default()
+            });
+        }
+    }
+
+    fn annotate_last(&mut self, field: &dyn Field) {
+        let with_class_ids = self.with_class_ids;
+        if let Some(last) = self.classes.last_mut().and_then(|c| c.fields.last_mut()) {
+            annotate(last, field, with_class_ids);
+        }
+    }
+}
+
+/// Stores state of the field and its members that isn't passed through [`Generator`].
+fn annotate(data: &mut DataField, field: &dyn Field, with_class_ids: bool) {
+    if let Some(state) = field.named_state() {
+        data.format = state.format.get();
+        data.note = state.note.borrow().clone();
+        data.expanded = state.expanded.get();
+    }
+
+    if with_class_ids {
+        data.class_id = field.target_class();
+    }
+
+    // Members are stored in the order they are generated in.
+    if let Some(members) = data.members.as_mut() {
+        let mut members = members.iter_mut();
+        field.for_each_member(&mut |member| {
+            if let Some(data) = members.next() {
+                annotate(data, member, with_class_ids);
+            }
+        });
+    }
+}
+
 impl ProjectData {
-    pub fn store(classes: &[Class]) -> Self {
+    pub fn store(
+        list: &ClassList,
+        watches: &[Watch],
+        inspector: &InspectorState,
+        target: &Target,
+    ) -> Self {
+        let classes = list.classes();
         let mut datagen = ProjectDataGenerator::default();
-        let dynam = &mut &mut datagen as &mut dyn Generator;
         let data = CodegenData { classes };
 
         for class in classes {
-            dynam.begin_class(&class.name);
+            let base = class.base(classes).map(|b| b.name.as_str());
+            (&mut datagen).begin_class(&class.name, base);
             for f in class.fields.iter() {
-                f.codegen(dynam, &data);
+                datagen.add(f.as_ref(), &data);
             }
-            dynam.end_class();
+            (&mut datagen).end_class();
+            let last = datagen.classes.last_mut().unwrap();
+            last.instances = class.instances.clone();
+            last.namespace = class.namespace.clone();
+            (last.size, last.alignment) = (class.size, class.alignment);
+            last.note = class.note.clone();
+            last.address = match class.address.get() {
+                0 => None,
+                address => Some(format!("0x{address:X}")),
+            };
         }
 
+        let watches = watches
+            .iter()
+            .filter_map(|w| {
+                let class = classes.iter().find(|c| c.id() == w.path.class_id)?;
+                Some(DataWatch {
+                    class: class.name.clone(),
+                    address: w.path.address.map(|a| format!("0x{a:X}")),
+                    links: w.path.links.clone(),
+                    offset: w.offset,
+                    kind: Some(w.kind),
+                    label: w.label.clone(),
+                })
+            })
+            .collect();
+
+        let name_of = |id| list.by_id(id).map(|c| c.name.clone());
+        let tabs = inspector
+            .tabs
+            .iter()
+            .filter_map(|t| {
+                Some(DataTab {
+                    class: name_of(t.class_id)?,
+                    address: format!("0x{:X}", t.address),
+                })
+            })
+            .collect::<Vec<_>>();
+        let inspector = DataInspector {
+            selected: list.selected().and_then(name_of),
+            active: inspector.active.filter(|&i| i < tabs.len()),
+            tabs,
+        };
+
         Self {
+            version: VERSION,
             classes: datagen.classes,
+            watches,
+            target: Some(target.clone()).filter(|t| *t != Target::default()),
+            inspector: Some(inspector),
         }
     }
 
-    pub fn load(self) -> ClassList {
+    /// Creates classes, the watch list and the inspector tabs.
+    /// Watches and tabs of missing classes are dropped.
+    pub fn load(self) -> LoadedProject {
         let mut list = ClassList::EMPTY;
 
         self.classes
             .iter()
             .for_each(|cl| _ = list.add_empty_class(cl.name.to_string()));
 
-        self.classes.into_iter().for_each(|mut dataclass| {
-            dataclass.fields.sort_by_key(|f| f.offset);
-
+        self.classes.into_iter().for_each(|dataclass| {
             let cid = list.by_name(&dataclass.name).unwrap().id();
-            let mut current_offset = 0;
-
-            for DataField {
-                offset: field_offset,
-                name,
-                kind,
-                metadata,
-            } in dataclass.fields
+
+            let base = dataclass.base.as_deref().and_then(|b| list.by_name(b));
+            if let Some(base) = base.map(|b| b.id()) {
+                if list.can_set_base(cid, base) {
+                    list.by_id_mut(cid).unwrap().base = Some(base);
+                }
+            }
+
+            let (mut fields, size) = load_fields(&mut list, dataclass.fields);
+            // Classes with the declared size are padded by the user.
+            if dataclass.size.is_none() && size % 8 != 0 {
+                fields.extend(allocate_padding(8 - (size % 8)));
+            }
+
+            let class = list.by_id_mut(cid).unwrap();
+            class.fields = fields;
+            // The first instance is opened by default.
+            let address = dataclass.address.as_deref().and_then(parse_address);
+            if let Some(address) =
+                address.or_else(|| dataclass.instances.first().and_then(|i| i.resolve()))
             {
-                let class = list.by_id_mut(cid).unwrap();
-                if field_offset > current_offset {
-                    class
-                        .fields
-                        .extend(allocate_padding(field_offset - current_offset));
+                class.address.set(address);
+            }
+            class.note = dataclass.note;
+            class.instances = dataclass.instances;
+            class.size = dataclass.size;
+            class.alignment = dataclass.alignment.filter(|a| a.is_power_of_two());
+            if is_valid_namespace(&dataclass.namespace) {
+                class.namespace = dataclass.namespace;
+            }
+        });
+
+        let watches = self
+            .watches
+            .into_iter()
+            .filter_map(|w| {
+                Some(Watch {
+                    path: WatchPath {
+                        class_id: list.by_name(&w.class)?.id(),
+                        address: match w.address {
+                            Some(address) => Some(parse_address(&address)?),
+                            None => None,
+                        },
+                        links: w.links,
+                    },
+                    offset: w.offset,
+                    kind: w.kind?,
+                    label: w.label,
+                })
+            })
+            .collect();
+
+        let mut inspector = InspectorState::default();
+        if let Some(data) = self.inspector {
+            *list.selected_mut() = data
+                .selected
+                .and_then(|name| Some(list.by_name(&name)?.id()));
+
+            // Indices of tabs shift if some of them are dropped.
+            let mut active = None;
+            for (i, tab) in data.tabs.into_iter().enumerate() {
+                let (Some(class), Some(address)) =
+                    (list.by_name(&tab.class), parse_address(&tab.address))
+                else {
+                    continue;
+                };
+                if data.active == Some(i) {
+                    active = Some(inspector.tabs.len());
                 }
+                inspector.tabs.push(InspectorTab {
+                    class_id: class.id(),
+                    address,
+                    seed: fastrand::u64(..),
+                });
+            }
+            inspector.active = active;
+        }
+
+        LoadedProject {
+            class_list: list,
+            watches,
+            inspector,
+            target: self.target.unwrap_or_default(),
+        }
+    }
+
+    /// Parses the project and migrates it to the current version.
+    pub fn from_str(text: &str) -> Result<Self, ProjectError> {
+        let text = &blank_unknown_payloads(text);
+        let header = ron::from_str::<ProjectHeader>(text)?;
+        if header.version > VERSION {
+            return Err(ProjectError::UnsupportedVersion(header.version));
+        }
+
+        let mut data = ron::from_str::<Self>(text)?;
+        for migrate in &MIGRATIONS[data.version as usize..] {
+            migrate(&mut data);
+        }
+        data.version = VERSION;
+
+        Ok(data)
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        ron::to_string(self).unwrap()
+    }
+}
+
+/// Version 0 didn't store sizes of fields, they are derived from kinds.
+fn migrate_v0(data: &mut ProjectData) {
+    fn fill_sizes(fields: &mut [DataField]) {
+        for field in fields.iter_mut() {
+            if let Some(members) = field.members.as_mut() {
+                fill_sizes(members);
+            }
+
+            field.size = match field.kind {
+                Some(FieldKind::Union) => field
+                    .members
+                    .as_ref()
+                    .and_then(|m| m.iter().filter_map(|m| m.size).max()),
+                kind => kind.map(|k| k.size()),
+            };
+        }
+    }
+
+    for class in data.classes.iter_mut() {
+        fill_sizes(&mut class.fields);
+    }
+}
+
+/// Replaces payloads of unknown field kinds, e.g. `(4)` of `kind: Array(4)`, with spaces.
+/// RON can't skip the payload of a variant without knowing if it's a tuple or a struct,
+/// blanking keeps the positions of parse errors intact.
+fn blank_unknown_payloads(text: &str) -> String {
+    fn skip_ident(bytes: &[u8], i: usize) -> usize {
+        i + bytes[i..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric() || **b == b'_')
+            .count()
+    }
+
+    fn skip_whitespace(bytes: &[u8], i: usize) -> usize {
+        i + bytes[i..]
+            .iter()
+            .take_while(|b| b.is_ascii_whitespace())
+            .count()
+    }
 
-                match kind {
-                    FieldKind::Ptr => {
-                        let classname = metadata.as_deref();
-                        if let Some(refclass) = classname.and_then(|name| list.by_name(name)) {
-                            let refid = refclass.id();
-                            let class = list.by_id_mut(cid).unwrap();
-                            class
-                                .fields
-                                .push(Box::new(PointerField::new_with_class_id(name, refid))
-                                    as Box<dyn Field>);
-                        } else {
-                            let new_cid = list.add_class(
-                                classname
-                                    .map(str::to_owned)
-                                    .unwrap_or_else(|| format!("C{:X}", field_offset)),
-                            );
-                            let class = list.by_id_mut(cid).unwrap();
-                            class
-                                .fields
-                                .push(Box::new(PointerField::new_with_class_id(name, new_cid))
-                                    as Box<dyn Field>);
-                        }
-                    }
-                    other => class.fields.push(other.into_field(Some(name))),
+    /// Skips the string or comment starting at `i`, if there is one.
+    fn skip_literal(bytes: &[u8], i: usize) -> Option<usize> {
+        match &bytes[i..] {
+            [b'"', ..] => {
+                let mut j = i + 1;
+                while j < bytes.len() && bytes[j] != b'"' {
+                    j += if bytes[j] == b'\\' { 2 } else { 1 };
                 }
+                Some((j + 1).min(bytes.len()))
+            }
+            [b'/', b'/', ..] => Some(
+                bytes[i..]
+                    .iter()
+                    .position(|b| *b == b'\n')
+                    .map_or(bytes.len(), |p| i + p),
+            ),
+            [b'/', b'*', ..] => Some(
+                bytes[i + 2..]
+                    .windows(2)
+                    .position(|w| w == b"*/")
+                    .map_or(bytes.len(), |p| i + p + 4),
+            ),
+            _ => None,
+        }
+    }
+
+    let mut bytes = text.as_bytes().to_vec();
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(end) = skip_literal(&bytes, i) {
+            i = end;
+            continue;
+        }
+        if !bytes[i].is_ascii_alphanumeric() && bytes[i] != b'_' {
+            i += 1;
+            continue;
+        }
 
-                current_offset = field_offset + kind.size();
+        let ident_end = skip_ident(&bytes, i);
+        let is_kind = &bytes[i..ident_end] == b"kind";
+        i = ident_end;
+
+        let colon = skip_whitespace(&bytes, i);
+        if !is_kind || bytes.get(colon) != Some(&b':') {
+            continue;
+        }
+        let name_start = skip_whitespace(&bytes, colon + 1);
+        let name_end = skip_ident(&bytes, name_start);
+        let open = skip_whitespace(&bytes, name_end);
+        let name = std::str::from_utf8(&bytes[name_start..name_end]).unwrap();
+        if name.is_empty() || bytes.get(open) != Some(&b'(') || known_kind::parse(name).is_some() {
+            continue;
+        }
+
+        // Unbalanced payloads are left as they are, RON reports them.
+        let mut depth = 0;
+        let mut j = open;
+        while j < bytes.len() {
+            if let Some(end) = skip_literal(&bytes, j) {
+                j = end;
+                continue;
+            }
+            match bytes[j] {
+                b'(' => depth += 1,
+                b')' => depth -= 1,
+                _ => {}
+            }
+            j += 1;
+            if depth == 0 {
+                break;
             }
+        }
+        if depth == 0 {
+            bytes[open..j]
+                .iter_mut()
+                .filter(|b| !b.is_ascii_whitespace())
+                .for_each(|b| *b = b' ');
+        }
+        i = j;
+    }
+
+    // Only ASCII bytes outside of strings were replaced.
+    String::from_utf8(bytes).unwrap()
+}
+
+/// Serializes field kinds, kinds unknown to this version are deserialized as `None`.
+mod known_kind {
+    use crate::field::FieldKind;
+    use serde::{
+        de::{self, EnumAccess, IntoDeserializer, VariantAccess, Visitor},
+        ser::Error,
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::fmt;
+
+    pub fn serialize<S: Serializer>(kind: &Option<FieldKind>, s: S) -> Result<S::Ok, S::Error> {
+        match kind {
+            Some(kind) => kind.serialize(s),
+            None => Err(S::Error::custom("unknown field kind can't be stored")),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<FieldKind>, D::Error> {
+        d.deserialize_enum("FieldKind", &[], KindVisitor)
+    }
+
+    struct KindVisitor;
+
+    impl<'de> Visitor<'de> for KindVisitor {
+        type Value = Option<FieldKind>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a field kind")
+        }
+
+        fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+            let (Ident(name), variant) = data.variant::<Ident>()?;
+            variant.unit_variant()?;
+            Ok(parse(&name))
+        }
+    }
+
+    /// Kind with the name, `None` if it's unknown to this version.
+    pub fn parse(name: &str) -> Option<FieldKind> {
+        let name: de::value::StrDeserializer<de::value::Error> = name.into_deserializer();
+        FieldKind::deserialize(name).ok()
+    }
+
+    /// Name of the variant, RON only provides it as an identifier.
+    struct Ident(String);
+
+    impl<'de> Deserialize<'de> for Ident {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            struct IdentVisitor;
+
+            impl Visitor<'_> for IdentVisitor {
+                type Value = Ident;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("an identifier")
+                }
 
-            if current_offset % 8 != 0 {
-                list.by_id_mut(cid)
-                    .unwrap()
-                    .fields
-                    .extend(allocate_padding(8 - (current_offset % 8)));
+                fn visit_str<E: de::Error>(self, v: &str) -> Result<Ident, E> {
+                    Ok(Ident(v.to_owned()))
+                }
             }
-        });
 
-        list
+            d.deserialize_identifier(IdentVisitor)
+        }
+    }
+}
+
+impl FieldClipboard {
+    pub fn store(fields: &[Box<dyn Field>], classes: &[Class]) -> Self {
+        let mut datagen = ProjectDataGenerator {
+            with_class_ids: true,
+            ..Default::default()
+        };
+        let data = CodegenData { classes };
+
+        (&mut datagen).begin_class("", None);
+        for f in fields {
+            datagen.add(f.as_ref(), &data);
+        }
+
+        Self {
+            instance: *INSTANCE_ID,
+            size: fields.iter().map(|f| f.size()).sum(),
+            fields: datagen.classes.pop().unwrap().fields,
+        }
+    }
+
+    /// Creates copied fields, missing classes pointers point to are created as well.
+    pub fn load(self, list: &mut ClassList) -> Vec<Box<dyn Field>> {
+        let mut fields = self.fields;
+        if self.instance != *INSTANCE_ID {
+            fields.iter_mut().for_each(|f| f.class_id = None);
+        }
+
+        let (mut loaded, size) = load_fields(list, fields);
+        if size < self.size {
+            loaded.extend(allocate_padding(self.size - size));
+        }
+        loaded
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Checks that the fields fit into the size and the size is sane,
+    /// pasted text could've been edited by hand.
+    pub fn fits(&self) -> bool {
+        self.size <= MAX_CLIPBOARD_SIZE
+            && self.fields.iter().all(|f| {
+                let size = f.size.or(f.kind.map(|k| k.size())).unwrap_or_default();
+                f.offset
+                    .checked_add(size)
+                    .is_some_and(|end| end <= self.size)
+            })
     }
 
     pub fn from_str(text: &str) -> Option<Self> {
-        ron::from_str(text).ok()
+        ron::from_str(text.trim()).ok()
     }
 
     #[allow(clippy::inherent_to_string)]
@@ -164,3 +779,254 @@ impl ProjectData {
         ron::to_string(self).unwrap()
     }
 }
+
+/// Identifies the running instance of the app.
+static INSTANCE_ID: Lazy<u64> = Lazy::new(|| fastrand::u64(..));
+
+/// Creates fields filling the gaps between them with padding.
+/// Returns the fields and their total size.
+fn load_fields(list: &mut ClassList, mut fields: Vec<DataField>) -> (Vec<Box<dyn Field>>, usize) {
+    fields.sort_by_key(|f| f.offset);
+
+    let mut loaded = vec![];
+    let mut current_offset = 0;
+    for field in fields {
+        let field_offset = field.offset;
+        if field_offset > current_offset {
+            loaded.extend(allocate_padding(field_offset - current_offset));
+        }
+
+        // Without the size the gap is padded up to the next field.
+        if field.kind.is_none() {
+            let size = field.size.unwrap_or_default();
+            loaded.extend(allocate_padding(size));
+            current_offset = current_offset.max(field_offset + size);
+            continue;
+        }
+
+        let field = load_field(list, field);
+        current_offset = field_offset + field.size();
+        loaded.push(field);
+    }
+
+    (loaded, current_offset)
+}
+
+fn load_field(list: &mut ClassList, field: DataField) -> Box<dyn Field> {
+    let DataField {
+        offset,
+        name,
+        kind,
+        metadata,
+        size: _,
+        members,
+        tag_offset,
+        format,
+        class_id,
+        note,
+        expanded,
+    } = field;
+
+    // Fields of unknown kinds are replaced with padding by the callers.
+    let kind = kind.unwrap_or(FieldKind::Unk8);
+    let field: Box<dyn Field> = match kind {
+        FieldKind::Ptr | FieldKind::RelPtr32 | FieldKind::RelPtr64 => {
+            let classname = metadata.as_deref().map(|m| split_pointer_metadata(m).0);
+            let refclass = class_id
+                .and_then(|id| list.by_id(id))
+                .or_else(|| classname.and_then(|name| list.by_name(name)));
+            let field = if let Some(refclass) = refclass {
+                PointerField::new_with_class_id(name, kind, refclass.id())
+            } else {
+                let new_cid = list.add_class(
+                    classname
+                        .map(str::to_owned)
+                        .unwrap_or_else(|| format!("C{:X}", offset)),
+                );
+                PointerField::new_with_class_id(name, kind, new_cid)
+            };
+
+            if let Some(metadata) = metadata.as_deref() {
+                field.load_metadata(metadata);
+            }
+            Box::new(field)
+        }
+        FieldKind::Union => {
+            let members = members
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|m| m.kind.is_some())
+                .map(|m| load_field(list, m))
+                .collect::<Vec<_>>();
+
+            if members.is_empty() {
+                FieldKind::Union.into_field(Some(name))
+            } else {
+                Box::new(UnionField::with_members(name, members, tag_offset))
+            }
+        }
+        other => {
+            let field = other.into_field(Some(name));
+            if let Some(metadata) = metadata.as_deref() {
+                field.load_metadata(metadata);
+            }
+            field
+        }
+    };
+
+    if let Some(state) = field.named_state() {
+        state.format.set(format);
+        *state.note.borrow_mut() = note;
+        state.expanded.set(expanded);
+    }
+    field
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::CppGenerator;
+
+    fn parse_kinds(kinds: &[&str]) -> Vec<(FieldKind, usize)> {
+        let fields = kinds
+            .iter()
+            .enumerate()
+            .map(|(i, kind)| {
+                format!(
+                    r#"(name: "f{i}", offset: {}, kind: {kind}, size: Some(4))"#,
+                    i * 4
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let text = format!(r#"(version: 1, classes: [(name: "A", fields: [{fields}])])"#);
+
+        let loaded = ProjectData::from_str(&text).unwrap().load();
+        let class = loaded.class_list.by_name("A").unwrap();
+        class.fields.iter().map(|f| (f.kind(), f.size())).collect()
+    }
+
+    #[test]
+    fn migrate_v0_fills_sizes() {
+        let text = r#"(classes: [(name: "A", fields: [
+            (name: "a", offset: 0, kind: F64, metadata: None),
+            (name: "b", offset: 8, kind: Union, metadata: None, members: Some([
+                (name: "x", offset: 0, kind: U16, metadata: None),
+                (name: "y", offset: 0, kind: U32, metadata: None),
+            ])),
+        ])])"#;
+
+        let data = ProjectData::from_str(text).unwrap();
+        assert_eq!(data.version, VERSION);
+
+        let fields = &data.classes[0].fields;
+        assert_eq!(fields[0].size, Some(8));
+        assert_eq!(fields[1].size, Some(4));
+        let members = fields[1].members.as_ref().unwrap();
+        assert_eq!((members[0].size, members[1].size), (Some(2), Some(4)));
+    }
+
+    #[test]
+    fn unknown_kinds_load_as_padding() {
+        for kind in [
+            "Array",
+            "Array(4)",
+            "Vec3(ty: F32)",
+            "Nested((1, \"(\"), [2])",
+        ] {
+            assert_eq!(
+                parse_kinds(&["F32", kind, "Ptr"]),
+                [
+                    (FieldKind::F32, 4),
+                    (FieldKind::Unk32, 4),
+                    (FieldKind::Ptr, 8)
+                ],
+                "{kind}"
+            );
+        }
+    }
+
+    #[test]
+    fn payloads_of_known_kinds_are_kept() {
+        let text = r#"(classes: [(name: "A", fields: [(name: "a", offset: 0, kind: U32(4))])])"#;
+        assert!(ProjectData::from_str(text).is_err());
+    }
+
+    #[test]
+    fn parse_errors_have_positions() {
+        let text = "(version: 1, classes: [\n    (name: \"A\", fields: [(name: 5)]),\n])";
+        let e = ProjectData::from_str(text).unwrap_err();
+        assert!(matches!(e, ProjectError::Parse { line: 2, .. }), "{e}");
+        assert!(e.to_string().starts_with("Line 2, column "), "{e}");
+
+        // Blanked payloads don't shift the positions.
+        let text = "(classes: [(name: \"A\", fields: [\n(kind: Array(4), name: 5)]),\n])";
+        let e = ProjectData::from_str(text).unwrap_err();
+        assert!(e.to_string().starts_with("Line 2, column 24: "), "{e}");
+    }
+
+    /// Stores the class list and loads it back.
+    fn round_trip(list: &ClassList) -> ClassList {
+        let data = ProjectData::store(list, &[], &InspectorState::default(), &Target::default());
+        ProjectData::from_str(&data.to_string())
+            .unwrap()
+            .load()
+            .class_list
+    }
+
+    #[test]
+    fn union_member_notes_round_trip() {
+        let mut list = ClassList::EMPTY;
+        let id = list.add_empty_class("A".into());
+        let union = UnionField::new("u".into());
+        union.for_each_member(&mut |m| {
+            *m.named_state().unwrap().note.borrow_mut() = format!("{} note", m.name().unwrap());
+        });
+        list.by_id_mut(id).unwrap().fields = vec![Box::new(union)];
+
+        let list = round_trip(&list);
+        let union = &list.by_name("A").unwrap().fields[0];
+        union.for_each_member(&mut |m| {
+            let note = m.named_state().unwrap().note.borrow().clone();
+            assert_eq!(note, format!("{} note", m.name().unwrap()));
+        });
+
+        let mut generator = CppGenerator::default();
+        generator.begin_class("A", None);
+        let data = CodegenData {
+            classes: list.classes(),
+        };
+        union.codegen(&mut generator, &data);
+        generator.end_class();
+        let code = generator.finilize();
+        union.for_each_member(&mut |m| {
+            assert!(
+                code.contains(&format!("// {} note", m.name().unwrap())),
+                "{code}"
+            );
+        });
+    }
+
+    #[test]
+    fn union_member_formats_round_trip() {
+        let mut list = ClassList::EMPTY;
+        let id = list.add_empty_class("A".into());
+        let union = UnionField::new("u".into());
+        let mut format = Some(DisplayFormat::Hex);
+        union.for_each_member(&mut |m| m.named_state().unwrap().format.set(format.take()));
+        list.by_id_mut(id).unwrap().fields = vec![Box::new(union)];
+
+        let list = round_trip(&list);
+        let mut formats = vec![];
+        list.by_name("A").unwrap().fields[0].for_each_member(&mut |m| {
+            formats.push(m.named_state().unwrap().format.get());
+        });
+        assert_eq!(formats, [Some(DisplayFormat::Hex), None]);
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let e = ProjectData::from_str("(version: 99, classes: [], unknown: 0)").unwrap_err();
+        assert!(matches!(e, ProjectError::UnsupportedVersion(99)));
+    }
+}
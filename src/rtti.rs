@@ -0,0 +1,167 @@
+//! This module resolves run-time type information of polymorphic objects.
+//! Only Itanium C++ ABI (GCC/Clang, used by Linux/ELF targets) is supported for now.
+//! Itanium layout:
+//! * `vtable[-1]` holds a pointer to the `std::type_info` of the object.
+//! * `type_info + 0x8` points to the mangled name of the type.
+//! * `__si_class_type_info + 0x10` points to the single base `type_info`.
+//! * `__vmi_class_type_info + 0x10` holds `flags: u32`, `base_count: u32`
+//!   followed by `base_count` entries of `{ base_type: *const type_info, offset_flags: i64 }`.
+use crate::process::YProcess;
+use cpp_demangle::Symbol;
+
+/// Maximum depth of base classes that are followed.
+const MAX_DEPTH: usize = 8;
+/// Maximum amount of bases a `__vmi_class_type_info` is allowed to have.
+const MAX_VMI_BASES: u32 = 32;
+/// Maximum length of the mangled type name.
+const MAX_NAME_LEN: usize = 256;
+
+#[derive(Debug, Clone)]
+pub struct TypeInfo {
+    /// Demangled name, e.g. `game::Player`.
+    pub name: String,
+    pub bases: Vec<TypeInfo>,
+}
+
+impl TypeInfo {
+    /// Name that can be used as a class name inside of yclass.
+    pub fn class_name(&self) -> String {
+        let name = self
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        let name = name.trim_matches('_');
+
+        if name.starts_with(char::is_numeric) {
+            format!("_{name}")
+        } else {
+            name.to_owned()
+        }
+    }
+
+    /// Formats type with all of its direct bases, e.g. `Player : Entity, Drawable`.
+    pub fn describe(&self) -> String {
+        if self.bases.is_empty() {
+            self.name.clone()
+        } else {
+            let bases = self
+                .bases
+                .iter()
+                .map(|b| b.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{} : {bases}", self.name)
+        }
+    }
+}
+
+/// Resolves type information of the object at `object` address, which is
+/// expected to start with a vtable pointer.
+pub fn resolve(process: &YProcess, object: usize) -> Option<TypeInfo> {
+    let vtable = read_ptr(process, object)?;
+    resolve_vtable(process, vtable)
+}
+
+/// Resolves type information using a pointer to the vtable(the one stored in objects).
+pub fn resolve_vtable(process: &YProcess, vtable: usize) -> Option<TypeInfo> {
+    if vtable < 8 || !vtable.is_multiple_of(8) {
+        return None;
+    }
+
+    let type_info = read_ptr(process, vtable - 8)?;
+    resolve_type_info(process, type_info, 0)
+}
+
+fn resolve_type_info(process: &YProcess, type_info: usize, depth: usize) -> Option<TypeInfo> {
+    if depth > MAX_DEPTH || type_info == 0 || !type_info.is_multiple_of(8) {
+        return None;
+    }
+
+    let name = read_type_name(process, type_info)?;
+    let bases = if depth == MAX_DEPTH {
+        vec![]
+    } else {
+        vmi_bases(process, type_info, depth)
+            .or_else(|| si_base(process, type_info, depth).map(|b| vec![b]))
+            .unwrap_or_default()
+    };
+
+    Some(TypeInfo {
+        name: demangle(&name),
+        bases,
+    })
+}
+
+fn vmi_bases(process: &YProcess, type_info: usize, depth: usize) -> Option<Vec<TypeInfo>> {
+    let mut buf = [0; 8];
+    process.read(type_info + 0x10, &mut buf);
+    let flags = u32::from_ne_bytes(buf[..4].try_into().unwrap());
+    let count = u32::from_ne_bytes(buf[4..].try_into().unwrap());
+
+    // Only `__non_diamond_repeat_mask` and `__diamond_shaped_mask` are defined.
+    if flags > 0b11 || count == 0 || count > MAX_VMI_BASES {
+        return None;
+    }
+
+    (0..count as usize)
+        .map(|i| {
+            let base = read_ptr(process, type_info + 0x18 + i * 0x10)?;
+            resolve_type_info(process, base, depth + 1)
+        })
+        .collect()
+}
+
+fn si_base(process: &YProcess, type_info: usize, depth: usize) -> Option<TypeInfo> {
+    let base = read_ptr(process, type_info + 0x10)?;
+    resolve_type_info(process, base, depth + 1)
+}
+
+fn read_type_name(process: &YProcess, type_info: usize) -> Option<String> {
+    let name_ptr = read_ptr(process, type_info + 8)?;
+
+    let mut buf = [0; MAX_NAME_LEN];
+    process.read(name_ptr, &mut buf);
+    let end = buf.iter().position(|b| *b == 0)?;
+    // GCC marks types with internal linkage with a leading `*`.
+    let name = buf[..end].strip_prefix(b"*").unwrap_or(&buf[..end]);
+
+    if is_mangled_type_name(name) {
+        Some(String::from_utf8_lossy(name).into_owned())
+    } else {
+        None
+    }
+}
+
+fn is_mangled_type_name(name: &[u8]) -> bool {
+    // Mangled names of class types always start either with a length
+    // of the source name, `N` for nested names or `S` for substitutions.
+    matches!(name.first(), Some(b'0'..=b'9' | b'N' | b'S' | b'Z'))
+        && name.iter().all(|b| b.is_ascii_alphanumeric() || *b == b'_')
+}
+
+fn demangle(name: &str) -> String {
+    const PREFIX: &str = "typeinfo name for ";
+
+    Symbol::new(format!("_ZTS{name}"))
+        .ok()
+        .map(|sym| sym.to_string())
+        .and_then(|s| s.strip_prefix(PREFIX).map(str::to_owned))
+        .unwrap_or_else(|| name.to_owned())
+}
+
+fn read_ptr(process: &YProcess, address: usize) -> Option<usize> {
+    if !process.can_read(address) {
+        return None;
+    }
+
+    let mut buf = [0; 8];
+    process.read(address, &mut buf);
+    let ptr = usize::from_ne_bytes(buf);
+
+    if ptr == 0 || !process.can_read(ptr) {
+        None
+    } else {
+        Some(ptr)
+    }
+}
@@ -1,6 +1,17 @@
 use crate::{
-    class::ClassList, config::YClassConfig, context::Selection, hotkeys::HotkeyManager,
-    process::YProcess, project::ProjectData,
+    changes::{ChangeTracker, DEFAULT_FADE_MS},
+    class::{ClassId, ClassList},
+    config::YClassConfig,
+    context::Selection,
+    field::FieldId,
+    freeze::{FreezeList, DEFAULT_INTERVAL_MS},
+    guess::Guesses,
+    history::History,
+    hotkeys::HotkeyManager,
+    process::YProcess,
+    project::{FieldClipboard, ProjectData, Target},
+    tabs::InspectorState,
+    watch::WatchList,
 };
 use egui_notify::Toasts;
 use parking_lot::RwLock;
@@ -9,7 +20,10 @@ use std::{
     collections::HashSet,
     fs,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
 use memflow::prelude::v1::*;
@@ -19,40 +33,141 @@ pub type StateRef = &'static RefCell<GlobalState>;
 pub struct GlobalState {
     pub last_opened_project: Option<PathBuf>,
     pub selection: Option<Selection>,
+    /// Fields that were copied last, pasted from the edit menu.
+    pub copied_fields: Option<FieldClipboard>,
     pub inventory: Inventory,
     pub os: Arc<RwLock<Option<OsInstanceArcBox<'static>>>>,
     pub process: Arc<RwLock<Option<YProcess>>>,
     pub hotkeys: HotkeyManager,
     pub class_list: ClassList,
+    pub history: History,
+    pub freezes: FreezeList,
+    /// Guessed kinds of unknown fields that weren't accepted yet.
+    pub guesses: Guesses,
+    /// Changes of values drawn in the inspector.
+    pub changes: ChangeTracker,
+    /// Fields pinned to the watch list, saved in the project.
+    pub watches: WatchList,
     pub config: YClassConfig,
     pub toasts: Toasts,
     /// `true` means project was just created and contains no useful
     /// information
     pub dummy: bool,
+    /// Name of the memflow connector that is currently used.
+    pub connector: Option<String>,
+    /// Tabs of the inspector, saved in the project.
+    pub inspector: InspectorState,
+    /// Process and connector of the project, saved in it.
+    pub target: Target,
+    /// Blocks all writes to the process memory, shared with [`YProcess`].
+    pub read_only: Arc<AtomicBool>,
 }
 
+/// Connectors that read memory dumps, writing to them makes no sense.
+const OFFLINE_CONNECTORS: &[&str] = &["coredump", "minidump", "dump"];
+
 impl Default for GlobalState {
     fn default() -> Self {
         let config = YClassConfig::load_or_default();
 
         let inventory = Inventory::scan();
+        let process = Arc::default();
+        let freezes = FreezeList::new(
+            Arc::clone(&process),
+            config.freeze_interval_ms.unwrap_or(DEFAULT_INTERVAL_MS),
+        );
 
         Self {
             hotkeys: HotkeyManager::default(),
             class_list: ClassList::default(),
+            history: History::default(),
+            freezes,
+            guesses: Guesses::default(),
+            watches: WatchList::default(),
+            changes: ChangeTracker::new(config.highlight_fade_ms.unwrap_or(DEFAULT_FADE_MS)),
             last_opened_project: None,
             toasts: Toasts::default(),
             inventory,
             os: Arc::default(),
-            process: Arc::default(),
+            process,
             selection: None,
+            copied_fields: None,
             dummy: true,
+            connector: None,
+            inspector: InspectorState::default(),
+            target: Target::default(),
+            read_only: Arc::default(),
             config,
         }
     }
 }
 
 impl GlobalState {
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::Relaxed)
+    }
+
+    /// Offline sources can't be switched out of the read-only mode.
+    pub fn is_read_only_forced(&self) -> bool {
+        self.connector
+            .as_deref()
+            .is_some_and(|c| OFFLINE_CONNECTORS.iter().any(|o| c.contains(o)))
+    }
+
+    /// Restores read-only mode that was saved for the connector.
+    pub fn set_connector(&mut self, connector: String) {
+        let saved = self
+            .config
+            .read_only_connectors
+            .as_ref()
+            .and_then(|m| m.get(&connector).copied())
+            .unwrap_or_default();
+
+        self.target.connector = Some(connector.clone());
+        self.connector = Some(connector);
+        let read_only = saved || self.is_read_only_forced();
+        self.read_only.store(read_only, Ordering::Relaxed);
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        if self.is_read_only_forced() {
+            return;
+        }
+
+        self.read_only.store(read_only, Ordering::Relaxed);
+        if let Some(connector) = self.connector.clone() {
+            self.config
+                .read_only_connectors
+                .get_or_insert_with(Default::default)
+                .insert(connector, read_only);
+            self.config.save();
+        }
+    }
+
+    /// Selects the class and the field of it, if any.
+    pub fn select_field(&mut self, class_id: ClassId, field_id: Option<FieldId>) {
+        *self.class_list.selected_mut() = Some(class_id);
+
+        let Some(field_id) = field_id else {
+            return;
+        };
+        let Some(class) = self.class_list.by_id(class_id) else {
+            return;
+        };
+        let Some(pos) = class.fields.iter().position(|f| f.id() == field_id) else {
+            return;
+        };
+
+        let base_size = self.class_list.base_size(class_id);
+        self.selection = Some(Selection {
+            address: class.address.get() + base_size + class.offset_of(pos),
+            container_id: class_id,
+            field_id,
+            class_address: class.address.get(),
+            others: vec![],
+        });
+    }
+
     pub fn save_project_as(&mut self) {
         if let Some(path) = rfd::FileDialog::new()
             .set_title("Save current project")
@@ -63,9 +178,19 @@ impl GlobalState {
         }
     }
 
+    fn store_project(&self) -> String {
+        ProjectData::store(
+            &self.class_list,
+            &self.watches.list(),
+            &self.inspector,
+            &self.target,
+        )
+        .to_string()
+    }
+
     pub fn save_project(&mut self, path: Option<&Path>) {
         if let Some(path) = path {
-            let pd = ProjectData::store(self.class_list.classes()).to_string();
+            let pd = self.store_project();
             if let Err(e) = fs::write(path, pd.as_bytes()) {
                 self.toasts
                     .error(format!("Failed to save the project. {e}"));
@@ -74,7 +199,7 @@ impl GlobalState {
                 self.dummy = false;
             }
         } else if let Some(ref last) = self.last_opened_project {
-            let pd = ProjectData::store(self.class_list.classes()).to_string();
+            let pd = self.store_project();
             if let Err(e) = fs::write(last, pd.as_bytes()) {
                 self.toasts
                     .error(format!("Failed to save the project. {e}"));
@@ -109,9 +234,15 @@ impl GlobalState {
         }
 
         match fs::read_to_string(path) {
-            Ok(data) => {
-                if let Some(pd) = ProjectData::from_str(&data) {
-                    self.class_list = pd.load();
+            Ok(data) => match ProjectData::from_str(&data) {
+                Ok(pd) => {
+                    let loaded = pd.load();
+                    self.class_list = loaded.class_list;
+                    self.watches.replace(loaded.watches);
+                    self.inspector = loaded.inspector;
+                    self.target = loaded.target;
+                    self.history.clear();
+                    self.selection = None;
                     self.dummy = false;
                     self.last_opened_project = Some(path.to_path_buf());
 
@@ -124,11 +255,13 @@ impl GlobalState {
                     self.config.save();
 
                     true
-                } else {
-                    self.toasts.error("Project file is in invalid format");
+                }
+                Err(e) => {
+                    self.toasts
+                        .error(format!("Project file is in invalid format. {e}"));
                     false
                 }
-            }
+            },
             Err(e) => {
                 self.toasts
                     .error(format!("Failed to open the project. {e}"));
@@ -0,0 +1,52 @@
+//! This module keeps classes opened in their own inspector tabs.
+//! Tabs are independent of the class list selection and are saved in the project.
+use crate::class::{Class, ClassId, ClassList};
+
+/// Class opened in its own inspector tab, independently of the class list selection.
+pub struct InspectorTab {
+    pub class_id: ClassId,
+    /// Address the class is inspected at.
+    pub address: usize,
+    /// Seeds ids of fields, so tabs don't share expanded pointers.
+    pub seed: u64,
+}
+
+/// Tabs of the inspector, saved in the project.
+#[derive(Default)]
+pub struct InspectorState {
+    /// Other tabs, the first tab always shows the class selected in the class list.
+    pub tabs: Vec<InspectorTab>,
+    /// `None` is the tab of the selected class.
+    pub active: Option<usize>,
+}
+
+impl InspectorState {
+    pub fn open(&mut self, class_id: ClassId, address: usize) {
+        self.tabs.push(InspectorTab {
+            class_id,
+            address,
+            seed: fastrand::u64(..),
+        });
+        self.active = Some(self.tabs.len() - 1);
+    }
+
+    /// Class of the active tab and the address it is inspected at.
+    pub fn target<'a>(&self, class_list: &'a ClassList) -> Option<(&'a Class, usize)> {
+        match self.active {
+            Some(i) => {
+                let tab = &self.tabs[i];
+                let class = class_list.by_id(tab.class_id)?;
+                Some((class, tab.address))
+            }
+            None => {
+                let class = class_list.selected_class()?;
+                Some((class, class.address.get()))
+            }
+        }
+    }
+
+    /// Seed of ids of fields in the active tab.
+    pub fn seed(&self) -> u64 {
+        self.active.map_or(0, |i| self.tabs[i].seed)
+    }
+}
@@ -0,0 +1,241 @@
+//! This module keeps fields pinned to the watch list.
+//! A watch is a path from the address of a class through a chain of pointers to a field,
+//! so it follows the field even when pointers change.
+use crate::{
+    address::parse_address,
+    class::{ClassId, ClassList},
+    field::{int_to_bytes, parse_float, parse_int, DisplayFormat, FieldKind},
+    process::YProcess,
+};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+
+/// Pointer followed on the way to the watched field.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    /// Offset of the pointer inside of the object.
+    pub offset: usize,
+    /// One of `Ptr`, `RelPtr32` or `RelPtr64`.
+    pub kind: FieldKind,
+    /// Offset inside of the class the pointer points to.
+    pub target_offset: usize,
+}
+
+impl Link {
+    /// Reads the pointer inside of the object at `address`, returns address of the object it points to.
+    pub fn follow(&self, process: &YProcess, address: usize) -> usize {
+        let paddr = address + self.offset;
+
+        let mut buf = [0; 8];
+        process.read(paddr, &mut buf[..self.kind.size()]);
+        let target = match self.kind {
+            FieldKind::RelPtr32 => {
+                paddr.wrapping_add_signed(i32::from_ne_bytes(buf[..4].try_into().unwrap()) as isize)
+            }
+            FieldKind::RelPtr64 => paddr.wrapping_add_signed(i64::from_ne_bytes(buf) as isize),
+            _ => usize::from_ne_bytes(buf),
+        };
+        target.wrapping_sub(self.target_offset)
+    }
+}
+
+/// Class the path starts from and pointers followed from its address.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WatchPath {
+    pub class_id: ClassId,
+    /// Address the path starts at, `None` for the address of the class.
+    /// Set for watches added in inspector tabs.
+    pub address: Option<usize>,
+    pub links: Vec<Link>,
+}
+
+impl WatchPath {
+    pub fn new(class_id: ClassId) -> Self {
+        Self {
+            class_id,
+            address: None,
+            links: vec![],
+        }
+    }
+
+    /// Path that continues through one more pointer.
+    pub fn with(&self, link: Link) -> Self {
+        let mut path = self.clone();
+        path.links.push(link);
+        path
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Watch {
+    pub path: WatchPath,
+    /// Offset of the field inside of the last object of the path.
+    pub offset: usize,
+    pub kind: FieldKind,
+    /// Name of the watched field, e.g. `Player.health`.
+    pub label: String,
+}
+
+impl Watch {
+    /// Address of the field, `None` if the class doesn't exist anymore
+    /// or one of the pointers can't be read.
+    pub fn resolve(&self, list: &ClassList, process: &YProcess) -> Option<usize> {
+        let class = list.by_id(self.path.class_id)?;
+        let mut address = self.path.address.unwrap_or(class.address.get());
+        for link in self.path.links.iter() {
+            address = link.follow(process, address);
+            if !process.can_read(address) {
+                return None;
+            }
+        }
+
+        Some(address + self.offset)
+    }
+
+    /// Short description of the path, e.g. `Player->[10]+8`.
+    pub fn describe(&self, list: &ClassList) -> String {
+        let mut text = list
+            .by_id(self.path.class_id)
+            .map(|c| c.name.clone())
+            .unwrap_or_else(|| "?".into());
+        if let Some(address) = self.path.address {
+            text += &format!("@{address:X}");
+        }
+        for link in self.path.links.iter() {
+            text += &format!("->[{:X}]", link.offset);
+        }
+        text + &format!("+{:X}", self.offset)
+    }
+}
+
+/// Formats bytes of the field of `kind` for the watch list.
+pub fn format_value(kind: FieldKind, bytes: &[u8]) -> String {
+    use FieldKind::*;
+
+    let mut buf = [0; 8];
+    buf[..bytes.len().min(8)].copy_from_slice(&bytes[..bytes.len().min(8)]);
+    let bits = u64::from_le_bytes(buf);
+
+    match kind {
+        I8 | I16 | I32 | I64 => DisplayFormat::Decimal.format_int(bits, kind.size(), true),
+        U8 | U16 | U32 | U64 => DisplayFormat::Decimal.format_int(bits, kind.size(), false),
+        F32 => f32::from_bits(bits as u32).to_string(),
+        F64 => f64::from_bits(bits).to_string(),
+        Bool => (bits != 0).to_string(),
+        Ptr | StrPtr | FnPtr | RelPtr32 | RelPtr64 => format!("{bits:X}"),
+        Unk8 | Unk16 | Unk32 | Unk64 | Union => bytes
+            .iter()
+            .map(|b| format!("{b:02X}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+/// Parses a value entered for the field of `kind`, `None` if it is invalid.
+pub fn parse_value(kind: FieldKind, text: &str) -> Option<Vec<u8>> {
+    use FieldKind::*;
+
+    let size = kind.size();
+    match kind {
+        F32 => Some((parse_float(text, size)? as f32).to_le_bytes().to_vec()),
+        F64 => Some(parse_float(text, size)?.to_le_bytes().to_vec()),
+        Bool => match text.trim() {
+            "true" => Some(vec![1]),
+            "false" => Some(vec![0]),
+            _ => None,
+        },
+        Ptr | StrPtr | FnPtr => Some(parse_address(text.trim())?.to_le_bytes().to_vec()),
+        RelPtr32 | RelPtr64 | Union => None,
+        _ => int_to_bytes(parse_int(text)?, size),
+    }
+}
+
+#[derive(Default)]
+pub struct WatchList {
+    watches: RefCell<Vec<Watch>>,
+}
+
+impl WatchList {
+    /// Adds the watch, unless the same field is already watched.
+    pub fn add(&self, watch: Watch) {
+        let mut watches = self.watches.borrow_mut();
+        if !watches
+            .iter()
+            .any(|w| w.path == watch.path && w.offset == watch.offset && w.kind == watch.kind)
+        {
+            watches.push(watch);
+        }
+    }
+
+    pub fn remove(&self, index: usize) {
+        self.watches.borrow_mut().remove(index);
+    }
+
+    pub fn list(&self) -> Vec<Watch> {
+        self.watches.borrow().clone()
+    }
+
+    pub fn replace(&self, watches: Vec<Watch>) {
+        *self.watches.borrow_mut() = watches;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_values() {
+        for (kind, bytes, expected) in [
+            (FieldKind::I8, &[0xFF][..], "-1"),
+            (FieldKind::U8, &[0xFF], "255"),
+            (FieldKind::I32, &[0xFE, 0xFF, 0xFF, 0xFF], "-2"),
+            (FieldKind::U16, &[0x34, 0x12], "4660"),
+            (FieldKind::F32, &1.5f32.to_le_bytes(), "1.5"),
+            (FieldKind::F64, &(-0.25f64).to_le_bytes(), "-0.25"),
+            (FieldKind::Bool, &[1], "true"),
+            (FieldKind::Bool, &[0], "false"),
+            (FieldKind::Ptr, &0x7FF0_1234u64.to_le_bytes(), "7FF01234"),
+            (FieldKind::Unk16, &[0xAB, 0x01], "AB 01"),
+        ] {
+            assert_eq!(format_value(kind, bytes), expected, "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn parses_values() {
+        for (kind, text, expected) in [
+            (FieldKind::I8, "-1", Some(vec![0xFF])),
+            (FieldKind::U8, "0x80", Some(vec![0x80])),
+            (FieldKind::U8, "256", None),
+            (FieldKind::U16, "'a'", Some(vec![b'a', 0])),
+            (FieldKind::F32, "1.5", Some(1.5f32.to_le_bytes().to_vec())),
+            (FieldKind::F64, "x", None),
+            (FieldKind::Bool, " true ", Some(vec![1])),
+            (FieldKind::Bool, "1", None),
+            (FieldKind::RelPtr32, "0", None),
+            (FieldKind::Union, "0", None),
+        ] {
+            assert_eq!(parse_value(kind, text), expected, "{kind:?} {text:?}");
+        }
+    }
+
+    #[test]
+    fn formatted_values_parse_back() {
+        for (kind, bytes) in [
+            (FieldKind::I16, &(-300i16).to_le_bytes()[..]),
+            (FieldKind::U64, &u64::MAX.to_le_bytes()),
+            (FieldKind::F32, &0.1f32.to_le_bytes()),
+            (FieldKind::F64, &1e-300f64.to_le_bytes()),
+            (FieldKind::Bool, &[1]),
+            (FieldKind::Ptr, &0x7FF0_1234u64.to_le_bytes()),
+        ] {
+            let text = format_value(kind, bytes);
+            assert_eq!(
+                parse_value(kind, &text).as_deref(),
+                Some(bytes),
+                "{kind:?} {text}"
+            );
+        }
+    }
+}